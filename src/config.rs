@@ -3,11 +3,13 @@ extern crate toml;
 
 use crate::{
     error::{Error, Kind},
-    throw,
+    pass, throw, xdg, Profile,
 };
 use std::borrow::Cow;
+use std::collections::BTreeMap;
 use std::fmt::{Display, Formatter};
-use std::path::PathBuf;
+use std::fs::OpenOptions;
+use std::path::{Path, PathBuf};
 
 /// Config type for parsing config files
 #[derive(Debug, serde::Deserialize)]
@@ -15,6 +17,16 @@ pub struct Config {
     data: PathBuf,
     steam: PathBuf,
     common: Option<PathBuf>,
+    default_version: Option<String>,
+    cache: Option<PathBuf>,
+    #[serde(default)]
+    exe_prefixes: bool,
+    #[serde(default)]
+    env: BTreeMap<String, String>,
+    #[serde(default)]
+    games: BTreeMap<String, Profile>,
+    #[serde(skip)]
+    no_cache: bool,
 }
 
 impl Config {
@@ -56,40 +68,95 @@ impl Config {
         Ok(config)
     }
 
-    /// Finds one of the two default config locations
+    /// Appends the game `name` to the end of the config file as a `[games.NAME]` table, leaving the
+    /// rest of the file, comments and all, as it is. `display_name` is the `name` it is shown with
     ///
     /// # Errors
     ///
-    /// Will only fail if `XDG_CONFIG_HOME` and `HOME` do not exist in environment
-    pub fn config_location() -> Result<PathBuf, Error> {
-        use std::env::var;
+    /// Will fail if the config location can not be found, or the config can not be written
+    pub fn append_game(
+        name: &str,
+        display_name: Option<&str>,
+        exe: &Path,
+        dir: Option<&Path>,
+        args: &[String],
+    ) -> Result<(), Error> {
+        use std::io::Write;
+
+        let mut table: toml::value::Table = toml::value::Table::new();
 
-        if let Ok(val) = var("XDG_CONFIG_HOME") {
-            let path: String = format!("{}/proton.conf", val);
-            return Ok(PathBuf::from(path));
+        if let Some(display_name) = display_name {
+            table.insert("name".to_string(), display_name.to_string().into());
         }
 
-        match var("HOME") {
-            Ok(var) => Ok(PathBuf::from(format!("{}/.config/proton.conf", var))),
-            Err(_) => throw!(Kind::Environment, "XDG_CONFIG_HOME / HOME missing"),
+        table.insert("exe".to_string(), exe.to_string_lossy().to_string().into());
+
+        if let Some(dir) = dir {
+            table.insert("dir".to_string(), dir.to_string_lossy().to_string().into());
+        }
+
+        if !args.is_empty() {
+            table.insert("args".to_string(), args.to_vec().into());
         }
+
+        let body: String = match toml::to_string(&table) {
+            Ok(body) => body,
+            Err(e) => throw!(Kind::ConfigWrite, "{}", e),
+        };
+
+        let bare: bool = !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        let key: String = if bare {
+            name.to_string()
+        } else {
+            toml::Value::String(name.to_string()).to_string()
+        };
+
+        let loc: PathBuf = Config::config_location()?;
+        let mut file: std::fs::File = match OpenOptions::new().append(true).open(&loc) {
+            Ok(f) => f,
+            Err(e) => throw!(Kind::ConfigWrite, "'{}': {}", loc.display(), e),
+        };
+
+        if let Err(e) = write!(file, "\n[games.{key}]\n{body}") {
+            throw!(Kind::ConfigWrite, "'{}': {}", loc.display(), e);
+        }
+
+        pass!()
+    }
+
+    #[must_use]
+    /// Returns the names of every game in the config
+    pub fn games(&self) -> Vec<String> {
+        self.games.keys().cloned().collect()
+    }
+
+    /// Finds one of the two default config locations
+    ///
+    /// # Errors
+    ///
+    /// Will only fail if `XDG_CONFIG_HOME` and `HOME` do not exist in environment
+    pub fn config_location() -> Result<PathBuf, Error> {
+        pass!(xdg::config_home()?.join("proton.conf"))
     }
 
     #[inline]
     /// Sets a default common if not given by user
     fn default_common(&mut self) {
         if self.common.is_none() {
-            let common: PathBuf = self._default_common();
+            let common: PathBuf = self.fallback_common();
             self.common = Some(common);
         }
     }
 
     #[must_use]
     /// Generates a default common directory
-    fn _default_common(&self) -> PathBuf {
+    fn fallback_common(&self) -> PathBuf {
         eprintln!("warning: using default common");
         let steam: Cow<str> = self.steam.to_string_lossy();
-        let common_str: String = format!("{}/steamapps/common/", steam);
+        let common_str: String = format!("{steam}/steamapps/common/");
         PathBuf::from(common_str)
     }
 
@@ -100,7 +167,7 @@ impl Config {
         if let Some(common) = &self.common {
             common.clone()
         } else {
-            self._default_common()
+            self.fallback_common()
         }
     }
 
@@ -117,6 +184,74 @@ impl Config {
     pub fn data(&self) -> PathBuf {
         self.data.clone()
     }
+
+    #[must_use]
+    #[inline]
+    /// Returns the Proton version to use when none is given
+    pub fn default_version(&self) -> Option<String> {
+        self.default_version.clone()
+    }
+
+    #[must_use]
+    #[inline]
+    /// Returns true if programs run without a profile or `--prefix` get a prefix of their own,
+    /// instead of sharing the prefix of their Proton version
+    pub fn exe_prefixes(&self) -> bool {
+        self.exe_prefixes
+    }
+
+    /// Returns the location of the index cache, `None` if the cache is disabled
+    ///
+    /// # Errors
+    ///
+    /// Will fail if no `cache` is given and `XDG_CACHE_HOME` and `HOME` do not exist in environment
+    pub fn cache(&self) -> Result<Option<PathBuf>, Error> {
+        if self.no_cache {
+            return pass!(None);
+        }
+
+        match &self.cache {
+            Some(cache) => pass!(Some(cache.clone())),
+            None => pass!(Some(xdg::cache_home()?.join("proton/index"))),
+        }
+    }
+
+    #[inline]
+    /// Disables the index cache, Proton is then indexed in memory on every run
+    pub fn disable_cache(&mut self) {
+        self.no_cache = true;
+    }
+
+    #[must_use]
+    /// Returns the environment variables from the `env` table, passed to every program
+    pub fn env(&self) -> Vec<(String, String)> {
+        self.env
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect()
+    }
+
+    #[must_use]
+    /// Returns the games run in `prefix`, a game runs in the prefix named after it unless given a `prefix`
+    pub fn games_in_prefix(&self, prefix: &str) -> Vec<String> {
+        self.games
+            .iter()
+            .filter(|(name, profile)| profile.prefix().as_ref().unwrap_or(name) == prefix)
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+
+    /// Returns the profile of the game `name` from the `games` table
+    ///
+    /// # Errors
+    ///
+    /// Will fail if no game named `name` is in the config
+    pub fn game(&self, name: &str) -> Result<Profile, Error> {
+        match self.games.get(name) {
+            Some(profile) => pass!(profile.clone()),
+            None => throw!(Kind::GameMissing, "'{}'", name),
+        }
+    }
 }
 
 impl Display for Config {
@@ -127,10 +262,10 @@ impl Display for Config {
         let common: String = if let Some(common) = &self.common {
             common.to_string_lossy().to_string()
         } else {
-            let pb: PathBuf = self._default_common();
+            let pb: PathBuf = self.fallback_common();
             pb.to_string_lossy().to_string()
         };
 
-        write!(f, "steam: {}\ndata: {}\ncommon: {}", steam, data, common)
+        write!(f, "steam: {steam}\ndata: {data}\ncommon: {common}")
     }
 }
@@ -0,0 +1,190 @@
+use crate::error::{Error, Kind};
+use crate::{pass, throw, xdg, Profile};
+use std::path::{Path, PathBuf};
+
+/// Freedesktop launcher for a game of the config, run through `proton-call --game`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DesktopEntry {
+    game: String,
+    name: String,
+    exec: Vec<String>,
+    dir: Option<PathBuf>,
+    wm_class: Option<String>,
+    icon: Option<String>,
+}
+
+/// Returns the desktop file ID of the launcher of the game `game`, which its icon is also named
+/// after
+#[must_use]
+pub fn id(game: &str) -> String {
+    // desktop file IDs may only hold letters, digits, `-` and `_`
+    let id: String = game
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+
+    format!("proton-call-{id}")
+}
+
+impl DesktopEntry {
+    #[must_use]
+    /// Creates the launcher of the game `game`, which runs `proton-call`, found at `exe`, with
+    /// `--game`, through the game's `wrapper` if it has one. The launcher is shown with the
+    /// game's `name`, or `game` otherwise
+    pub fn new(game: &str, profile: &Profile, exe: &Path) -> DesktopEntry {
+        let mut exec: Vec<String> = profile.wrapper();
+        exec.extend([
+            exe.to_string_lossy().to_string(),
+            "--game".to_string(),
+            game.to_string(),
+        ]);
+
+        DesktopEntry {
+            game: game.to_string(),
+            name: profile.name().unwrap_or_else(|| game.to_string()),
+            exec,
+            dir: profile.dir(),
+            // Wine names the windows of a program after its executable, in lowercase
+            wm_class: profile
+                .exe()
+                .file_name()
+                .map(|name| name.to_string_lossy().to_lowercase()),
+            icon: None,
+        }
+    }
+
+    #[must_use]
+    /// Sets the icon of the launcher, by its name in the icon theme or its path
+    pub fn with_icon(mut self, icon: Option<String>) -> DesktopEntry {
+        self.icon = icon;
+        self
+    }
+
+    /// Returns where the launcher of the game `game` is installed, in `$XDG_DATA_HOME/applications`
+    ///
+    /// # Errors
+    ///
+    /// Will fail if neither `XDG_DATA_HOME` nor `HOME` are set
+    pub fn location(game: &str) -> Result<PathBuf, Error> {
+        pass!(xdg::data_home()?.join(format!("applications/{}.desktop", id(game))))
+    }
+
+    /// Writes the launcher into `$XDG_DATA_HOME/applications`, replacing the one already there,
+    /// and returns where it was written
+    ///
+    /// # Errors
+    ///
+    /// Will fail if the data directory can not be found, or the launcher can not be written
+    pub fn install(&self) -> Result<PathBuf, Error> {
+        let path: PathBuf = DesktopEntry::location(&self.game)?;
+
+        let result: std::io::Result<()> = (|| {
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+
+            std::fs::write(&path, self.to_string())
+        })();
+
+        match result {
+            Ok(()) => pass!(path),
+            Err(e) => throw!(Kind::Desktop, "'{}': {}", path.display(), e),
+        }
+    }
+
+    /// Removes the launcher of the game `game`, returns where it was
+    ///
+    /// # Errors
+    ///
+    /// Will fail if the game has no launcher, or it can not be removed
+    pub fn remove(game: &str) -> Result<PathBuf, Error> {
+        let path: PathBuf = DesktopEntry::location(game)?;
+
+        if !path.exists() {
+            throw!(
+                Kind::Desktop,
+                "'{}' has no launcher in '{}'",
+                game,
+                path.display()
+            );
+        }
+
+        if let Err(e) = std::fs::remove_file(&path) {
+            throw!(Kind::Desktop, "'{}': {}", path.display(), e);
+        }
+
+        pass!(path)
+    }
+}
+
+impl std::fmt::Display for DesktopEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let exec: Vec<String> = self.exec.iter().map(|arg| exec_quote(arg)).collect();
+
+        writeln!(f, "[Desktop Entry]")?;
+        writeln!(f, "Version=1.0")?;
+        writeln!(f, "Type=Application")?;
+        writeln!(f, "Name={}", escape(&self.name))?;
+        writeln!(
+            f,
+            "Comment={}",
+            escape(&format!("Run {} with Proton", self.name))
+        )?;
+        writeln!(f, "Exec={}", escape(&exec.join(" ")))?;
+
+        if let Some(icon) = &self.icon {
+            writeln!(f, "Icon={}", escape(icon))?;
+        }
+
+        if let Some(dir) = &self.dir {
+            writeln!(f, "Path={}", escape(&dir.to_string_lossy()))?;
+        }
+
+        if let Some(wm_class) = &self.wm_class {
+            writeln!(f, "StartupWMClass={}", escape(wm_class))?;
+        }
+
+        writeln!(f, "Terminal=false")?;
+        writeln!(f, "StartupNotify=false")?;
+        writeln!(f, "Categories=Game;")?;
+        writeln!(f, "X-Proton-Call-Game={}", escape(&self.game))
+    }
+}
+
+/// Escapes a string value of a desktop entry
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('\n', "\\n")
+        .replace('\t', "\\t")
+        .replace('\r', "\\r")
+}
+
+/// Quotes an argument of `Exec` the way the desktop entry specification requires, before the
+/// value itself is escaped. `%` is doubled so it is not taken for a field code
+fn exec_quote(arg: &str) -> String {
+    let arg: String = arg.replace('%', "%%");
+    let reserved: bool = arg.is_empty()
+        || arg.contains(|c: char| c.is_whitespace() || "\"'\\><~|&;$*?#()`".contains(c));
+
+    if !reserved {
+        return arg;
+    }
+
+    let mut quoted: String = String::from('"');
+
+    for c in arg.chars() {
+        if matches!(c, '"' | '`' | '$' | '\\') {
+            quoted.insert(quoted.len(), '\\');
+        }
+        quoted.insert(quoted.len(), c);
+    }
+
+    quoted.insert(quoted.len(), '"');
+    quoted
+}
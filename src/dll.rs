@@ -0,0 +1,156 @@
+use std::{
+    fmt::{Display, Formatter},
+    str::FromStr,
+};
+
+use crate::{error::Kind, pass, throw};
+
+/// Where Wine loads a DLL from, as a letter in `WINEDLLOVERRIDES`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LoadOrder {
+    /// Windows DLL in the prefix or next to the program, `n`
+    Native,
+    /// Wine's own DLL, `b`
+    Builtin,
+}
+
+impl Display for LoadOrder {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoadOrder::Native => write!(f, "n"),
+            LoadOrder::Builtin => write!(f, "b"),
+        }
+    }
+}
+
+/// Override of the load order of DLLs, given as `NAME[,NAME]=ORDER` the way `WINEDLLOVERRIDES`
+/// takes it, such as `dinput8=n,b`. An empty order, or `d`, disables the DLLs
+#[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize)]
+#[serde(try_from = "String")]
+pub struct DllOverride {
+    dlls: Vec<String>,
+    order: Vec<LoadOrder>,
+}
+
+impl DllOverride {
+    #[must_use]
+    #[inline]
+    /// Returns the names of the DLLs overridden
+    pub fn dlls(&self) -> Vec<String> {
+        self.dlls.clone()
+    }
+
+    #[must_use]
+    #[inline]
+    /// Returns the load order of the DLLs, first tried first, empty if they are disabled
+    pub fn order(&self) -> Vec<LoadOrder> {
+        self.order.clone()
+    }
+
+    /// Returns true if this overrides `dll`, which may be given with or without `.dll`
+    fn overrides(&self, dll: &str) -> bool {
+        self.dlls.iter().any(|d| key(d) == key(dll))
+    }
+}
+
+/// Compares DLL names the way Wine does, without case or the `.dll` extension
+fn key(dll: &str) -> String {
+    let dll: String = dll.to_lowercase();
+    dll.strip_suffix(".dll").unwrap_or(&dll).to_string()
+}
+
+/// Merges `overrides` into `base`, an existing `WINEDLLOVERRIDES` value. Overrides replace the
+/// entries of `base` for the same DLLs, and later overrides replace earlier ones. Entries of
+/// `base` which are not valid are kept as they are
+#[must_use]
+pub fn merge(base: &str, overrides: &[DllOverride]) -> String {
+    let mut entries: Vec<String> = Vec::new();
+
+    for group in base.split(';').filter(|g| !g.trim().is_empty()) {
+        match group.parse::<DllOverride>() {
+            Ok(mut entry) => {
+                entry
+                    .dlls
+                    .retain(|dll| !overrides.iter().any(|o| o.overrides(dll)));
+
+                if !entry.dlls.is_empty() {
+                    entries.insert(entries.len(), entry.to_string());
+                }
+            }
+            Err(_) => entries.insert(entries.len(), group.to_string()),
+        }
+    }
+
+    for (i, entry) in overrides.iter().enumerate() {
+        let mut entry: DllOverride = entry.clone();
+        entry
+            .dlls
+            .retain(|dll| !overrides[i + 1..].iter().any(|o| o.overrides(dll)));
+
+        if !entry.dlls.is_empty() {
+            entries.insert(entries.len(), entry.to_string());
+        }
+    }
+
+    entries.join(";")
+}
+
+impl Display for DllOverride {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let order: Vec<String> = self.order.iter().map(ToString::to_string).collect();
+        write!(f, "{}={}", self.dlls.join(","), order.join(","))
+    }
+}
+
+impl FromStr for DllOverride {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |why: &str| -> Result<DllOverride, crate::Error> {
+            throw!(
+                Kind::ParseDllOverride,
+                "'{}' {}, expected NAME[,NAME]=ORDER where ORDER is n, b, n,b, b,n, or empty to disable",
+                s,
+                why
+            )
+        };
+
+        let Some((names, order)) = s.split_once('=') else {
+            return invalid("has no load order");
+        };
+
+        let dlls: Vec<String> = names.split(',').map(|n| n.trim().to_string()).collect();
+
+        if dlls.iter().any(|dll| dll.is_empty() || dll.contains(';')) {
+            return invalid("has an invalid DLL name");
+        }
+
+        let mut loads: Vec<LoadOrder> = Vec::new();
+
+        if !matches!(order.trim(), "" | "d") {
+            for letter in order.split(',') {
+                let load: LoadOrder = match letter.trim() {
+                    "n" => LoadOrder::Native,
+                    "b" => LoadOrder::Builtin,
+                    _ => return invalid("has an invalid load order"),
+                };
+
+                if loads.contains(&load) {
+                    return invalid("repeats a load order");
+                }
+
+                loads.insert(loads.len(), load);
+            }
+        }
+
+        pass!(DllOverride { dlls, order: loads })
+    }
+}
+
+impl TryFrom<String> for DllOverride {
+    type Error = crate::Error;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
@@ -18,15 +18,15 @@ macro_rules! pass {
 #[macro_export]
 macro_rules! throw {
     ($kind:expr, $fmt:literal) => ({
-        return $crate::error::_throw($kind, std::format!($fmt))
+        return $crate::error::throw_error($kind, std::format!($fmt))
     });
     ($kind:expr, $fmt:literal, $($arg:tt)*) => ({
-        return $crate::error::_throw($kind, std::format!($fmt, $($arg)*))
+        return $crate::error::throw_error($kind, std::format!($fmt, $($arg)*))
     })
 }
 
 #[doc(hidden)]
-pub fn _throw<T>(kind: Kind, inner: String) -> Result<T, Error> {
+pub fn throw_error<T>(kind: Kind, inner: String) -> Result<T, Error> {
     Err(Error::new(kind, inner))
 }
 
@@ -119,10 +119,46 @@ pub enum Kind {
     JargonInternal,
     /// for when Index failes an action with cache
     IndexCache,
-    /// for when parsing RuntimeOption fails,
+    /// for when parsing `RuntimeOption` fails,
     ParseRuntimeOpt,
     /// for when steam runtime version is missing
     RuntimeMissing,
+    /// for when a game is missing from the config
+    GameMissing,
+    /// for when a VDF file fails to be read
+    VdfRead,
+    /// for when a VDF file fails to be parsed
+    VdfParse,
+    /// for when Index fails to read or write the Proton builds it was given
+    IndexState,
+    /// for when a command line argument has an invalid value
+    ArgumentInvalid,
+    /// for when a prefix name can not be used as a directory
+    PrefixName,
+    /// for when a prefix is not found in the data directory
+    PrefixMissing,
+    /// for when a prefix is created over an existing one
+    PrefixExists,
+    /// for when reading or writing a prefix fails
+    PrefixIo,
+    /// for when a prefix would be run with an older Proton than it was last run with
+    PrefixDowngrade,
+    /// for when backing up or restoring a prefix fails
+    PrefixArchive,
+    /// for when winetricks fails to run or apply a verb
+    Winetricks,
+    /// for when a registry file can not be read or imported
+    Registry,
+    /// for when a DLL override is not valid
+    ParseDllOverride,
+    /// for when the config file can not be written
+    ConfigWrite,
+    /// for when Steam's shortcuts can not be read or written
+    Shortcuts,
+    /// for when a desktop launcher can not be written or removed
+    Desktop,
+    /// for when the icon of a program can not be extracted or installed
+    Icon,
 }
 
 impl Display for Kind {
@@ -149,6 +185,24 @@ impl Display for Kind {
                 Kind::IndexCache => "failed read/write to cache",
                 Kind::ParseRuntimeOpt => "failed parsing runtime option",
                 Kind::RuntimeMissing => "failed to find Runtime",
+                Kind::GameMissing => "cannot find game in config",
+                Kind::VdfRead => "failed to read VDF",
+                Kind::VdfParse => "failed to parse VDF",
+                Kind::IndexState => "failed read/write to index state",
+                Kind::ArgumentInvalid => "invalid command line argument",
+                Kind::PrefixName => "invalid prefix name",
+                Kind::PrefixMissing => "cannot find prefix",
+                Kind::PrefixExists => "prefix already exists",
+                Kind::PrefixIo => "failed read/write to prefix",
+                Kind::PrefixDowngrade => "refusing to downgrade prefix",
+                Kind::PrefixArchive => "failed to archive prefix",
+                Kind::Winetricks => "failed to run winetricks",
+                Kind::Registry => "failed to import registry file",
+                Kind::ParseDllOverride => "failed parsing DLL override",
+                Kind::ConfigWrite => "failed to write config",
+                Kind::Shortcuts => "failed read/write to Steam shortcuts",
+                Kind::Desktop => "failed read/write to desktop launcher",
+                Kind::Icon => "failed to extract icon",
             }
         )
     }
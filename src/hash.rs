@@ -0,0 +1,31 @@
+/// FNV-1a hash of `bytes`, stable between builds so it can be kept on disk
+pub fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash: u64, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// CRC-32 of `bytes`, as zlib and Steam compute it
+pub fn crc32(bytes: &[u8]) -> u32 {
+    let crc: u32 = bytes.iter().fold(0xFFFF_FFFF, |crc: u32, byte| {
+        (0..8).fold(crc ^ u32::from(*byte), |crc: u32, _| {
+            if crc & 1 == 1 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            }
+        })
+    });
+
+    !crc
+}
+
+/// Adler-32 of `bytes`, the checksum ending zlib streams
+pub fn adler32(bytes: &[u8]) -> u32 {
+    let (a, b): (u32, u32) = bytes.iter().fold((1, 0), |(a, b), byte| {
+        let a: u32 = (a + u32::from(*byte)) % 65_521;
+        (a, (b + a) % 65_521)
+    });
+
+    (b << 16) | a
+}
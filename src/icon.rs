@@ -0,0 +1,324 @@
+use crate::error::{Error, Kind};
+use crate::hash::{adler32, crc32};
+use crate::pe::{u16_at, u32_at, Resources, RT_GROUP_ICON, RT_ICON};
+use crate::{pass, throw, xdg};
+use std::path::{Path, PathBuf};
+
+/// Sizes the hicolor icon theme has directories for, icons of other sizes are not looked up
+const HICOLOR_SIZES: [u32; 13] = [16, 22, 24, 32, 36, 48, 64, 72, 96, 128, 192, 256, 512];
+
+/// Image of an icon, as PNG
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IconImage {
+    width: u32,
+    height: u32,
+    depth: u16,
+    png: Vec<u8>,
+}
+
+impl IconImage {
+    #[must_use]
+    #[inline]
+    /// Returns the width of the image
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    #[must_use]
+    #[inline]
+    /// Returns the height of the image
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    #[must_use]
+    #[inline]
+    /// Returns the image as a PNG file
+    pub fn png(&self) -> Vec<u8> {
+        self.png.clone()
+    }
+
+    /// Reads an `RT_ICON` resource, which is either a PNG file or a DIB without its file header
+    fn from_resource(data: &[u8]) -> Option<IconImage> {
+        if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+            // IHDR is always the first chunk
+            let width: u32 = u32::from_be_bytes(data.get(16..20)?.try_into().ok()?);
+            let height: u32 = u32::from_be_bytes(data.get(20..24)?.try_into().ok()?);
+
+            return Some(IconImage {
+                width,
+                height,
+                depth: 32,
+                png: data.to_vec(),
+            });
+        }
+
+        let (width, height, depth, rgba) = decode_dib(data)?;
+
+        Some(IconImage {
+            width,
+            height,
+            depth,
+            png: encode_png(width, height, &rgba),
+        })
+    }
+}
+
+/// Icon of a Windows program, as the images of its first `RT_GROUP_ICON` resource, the icon
+/// Windows shows it with
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Icon {
+    images: Vec<IconImage>,
+}
+
+impl Icon {
+    /// Extracts the icon of the Windows program `exe`, keeping the deepest image of each square
+    /// size, largest first
+    ///
+    /// # Errors
+    ///
+    /// Will fail if `exe` can not be read, is not a Windows program or has no icon
+    pub fn extract(exe: &Path) -> Result<Icon, Error> {
+        let bytes: Vec<u8> = match std::fs::read(exe) {
+            Ok(bytes) => bytes,
+            Err(e) => throw!(Kind::Icon, "'{}': {}", exe.display(), e),
+        };
+
+        let resources: Resources = match Resources::parse(&bytes) {
+            Some(resources) => resources,
+            None => throw!(
+                Kind::Icon,
+                "'{}': not a Windows program with resources",
+                exe.display()
+            ),
+        };
+
+        let group: &[u8] = match resources.list(RT_GROUP_ICON).first() {
+            Some((_, group)) => group,
+            None => throw!(Kind::Icon, "'{}' has no icon", exe.display()),
+        };
+
+        let icons: Vec<(Option<u32>, &[u8])> = resources.list(RT_ICON);
+        let count: usize = u16_at(group, 4).unwrap_or_default().into();
+        let mut images: Vec<IconImage> = Vec::new();
+
+        // GRPICONDIR entries are 14 bytes, ending with the ID of their RT_ICON
+        for entry in 0..count {
+            let image: Option<IconImage> = u16_at(group, 6 + entry * 14 + 12)
+                .and_then(|id| icons.iter().find(|(i, _)| *i == Some(id.into())))
+                .and_then(|(_, data)| IconImage::from_resource(data));
+
+            let image: IconImage = match image {
+                Some(image) if image.width == image.height => image,
+                _ => continue,
+            };
+
+            match images.iter_mut().find(|i| i.width == image.width) {
+                Some(kept) if kept.depth < image.depth => *kept = image,
+                Some(_) => {}
+                None => images.insert(images.len(), image),
+            }
+        }
+
+        if images.is_empty() {
+            throw!(
+                Kind::Icon,
+                "'{}': no image of its icon can be read",
+                exe.display()
+            );
+        }
+
+        images.sort_by_key(|image| std::cmp::Reverse(image.width));
+        pass!(Icon { images })
+    }
+
+    #[must_use]
+    #[inline]
+    /// Returns the images of the icon, largest first
+    pub fn images(&self) -> Vec<IconImage> {
+        self.images.clone()
+    }
+
+    /// Writes the images of the sizes the hicolor theme has into
+    /// `$XDG_DATA_HOME/icons/hicolor/SIZExSIZE/apps/NAME.png`, so the icon can be used by `name`.
+    /// Returns the files written, largest first
+    ///
+    /// # Errors
+    ///
+    /// Will fail if the data directory can not be found, no image has a size the theme has, or
+    /// an image can not be written
+    pub fn install(&self, name: &str) -> Result<Vec<PathBuf>, Error> {
+        let hicolor: PathBuf = xdg::data_home()?.join("icons/hicolor");
+        let mut installed: Vec<PathBuf> = Vec::new();
+
+        for image in &self.images {
+            if !HICOLOR_SIZES.contains(&image.width) {
+                continue;
+            }
+
+            let dir: PathBuf = hicolor.join(format!("{0}x{0}/apps", image.width));
+            let path: PathBuf = dir.join(format!("{name}.png"));
+
+            if let Err(e) =
+                std::fs::create_dir_all(&dir).and_then(|()| std::fs::write(&path, &image.png))
+            {
+                throw!(Kind::Icon, "'{}': {}", path.display(), e);
+            }
+
+            installed.insert(installed.len(), path);
+        }
+
+        if installed.is_empty() {
+            throw!(
+                Kind::Icon,
+                "no image of the icon has a size of the hicolor theme"
+            );
+        }
+
+        pass!(installed)
+    }
+
+    /// Removes the images installed as `name` from every size of the hicolor theme, returns the
+    /// files removed
+    ///
+    /// # Errors
+    ///
+    /// Will fail if the data directory can not be found, or an image can not be removed
+    pub fn remove(name: &str) -> Result<Vec<PathBuf>, Error> {
+        let hicolor: PathBuf = xdg::data_home()?.join("icons/hicolor");
+        let mut removed: Vec<PathBuf> = Vec::new();
+
+        for dir in hicolor.read_dir().into_iter().flatten().flatten() {
+            let path: PathBuf = dir.path().join(format!("apps/{name}.png"));
+
+            if !path.exists() {
+                continue;
+            }
+
+            if let Err(e) = std::fs::remove_file(&path) {
+                throw!(Kind::Icon, "'{}': {}", path.display(), e);
+            }
+
+            removed.insert(removed.len(), path);
+        }
+
+        pass!(removed)
+    }
+}
+
+/// Decodes the DIB of an icon into its width, height, bit depth and RGBA pixels, top row first.
+/// The DIB is twice the height of the icon, its colors followed by a 1 bit transparency mask
+fn decode_dib(data: &[u8]) -> Option<(u32, u32, u16, Vec<u8>)> {
+    let header: usize = u32_at(data, 0)? as usize;
+    let width: u32 = u32_at(data, 4)?;
+    let height: u32 = u32_at(data, 8)? / 2;
+    let depth: u16 = u16_at(data, 14)?;
+
+    // only uncompressed images are used in icons
+    if u32_at(data, 16)? != 0 || width == 0 || height == 0 || width > 1024 || height > 1024 {
+        return None;
+    }
+
+    let colors: usize = match (depth, u32_at(data, 32)?) {
+        (1 | 4 | 8, 0) => 1 << depth,
+        (1 | 4 | 8, used) => used as usize,
+        (24 | 32, _) => 0,
+        _ => return None,
+    };
+
+    let palette: &[u8] = data.get(header..header + colors * 4)?;
+    let pixels: usize = header + colors * 4;
+    let stride: usize = (width as usize * usize::from(depth)).div_ceil(32) * 4;
+    let mask: usize = pixels + stride * height as usize;
+    let mask_stride: usize = (width as usize).div_ceil(32) * 4;
+
+    let mut rgba: Vec<u8> = Vec::with_capacity(width as usize * height as usize * 4);
+
+    // rows are kept bottom up
+    for y in (0..height as usize).rev() {
+        let row: &[u8] = data.get(pixels + y * stride..pixels + (y + 1) * stride)?;
+
+        for x in 0..width as usize {
+            let [b, g, r, a]: [u8; 4] = match depth {
+                32 => row.get(x * 4..x * 4 + 4)?.try_into().ok()?,
+                24 => {
+                    let [b, g, r]: [u8; 3] = row.get(x * 3..x * 3 + 3)?.try_into().ok()?;
+                    [b, g, r, 255]
+                }
+                _ => {
+                    let bit: usize = x * usize::from(depth);
+                    let byte: u8 = *row.get(bit / 8)?;
+                    let index: usize = (usize::from(byte) >> (8 - usize::from(depth) - bit % 8))
+                        & ((1 << depth) - 1);
+                    let [b, g, r, _]: [u8; 4] =
+                        palette.get(index * 4..index * 4 + 4)?.try_into().ok()?;
+                    [b, g, r, 255]
+                }
+            };
+
+            rgba.extend_from_slice(&[r, g, b, a]);
+        }
+    }
+
+    // the mask is only used when the image has no alpha of its own
+    if depth != 32 || rgba.chunks(4).all(|pixel| pixel[3] == 0) {
+        for (row, y) in (0..height as usize).rev().enumerate() {
+            let bits: Option<&[u8]> =
+                data.get(mask + y * mask_stride..mask + (y + 1) * mask_stride);
+
+            for x in 0..width as usize {
+                let transparent: bool =
+                    bits.is_some_and(|bits| bits[x / 8] & (0x80 >> (x % 8)) != 0);
+                rgba[(row * width as usize + x) * 4 + 3] = if transparent { 0 } else { 255 };
+            }
+        }
+    }
+
+    Some((width, height, depth, rgba))
+}
+
+/// Encodes RGBA pixels, top row first, as a PNG file. The pixels are kept in stored deflate
+/// blocks, icons are small enough to not need compressing
+fn encode_png(width: u32, height: u32, rgba: &[u8]) -> Vec<u8> {
+    let row: usize = width as usize * 4;
+
+    // each row starts with its filter, none
+    let mut raw: Vec<u8> = Vec::with_capacity((row + 1) * height as usize);
+    for line in rgba.chunks(row) {
+        raw.insert(raw.len(), 0);
+        raw.extend_from_slice(line);
+    }
+
+    let mut zlib: Vec<u8> = vec![0x78, 0x01];
+    let blocks: Vec<&[u8]> = raw.chunks(0xFFFF).collect();
+    for (i, block) in blocks.iter().enumerate() {
+        let len: u16 = u16::try_from(block.len()).unwrap_or(u16::MAX);
+        zlib.insert(zlib.len(), u8::from(i + 1 == blocks.len()));
+        zlib.extend_from_slice(&len.to_le_bytes());
+        zlib.extend_from_slice(&(!len).to_le_bytes());
+        zlib.extend_from_slice(block);
+    }
+    zlib.extend_from_slice(&adler32(&raw).to_be_bytes());
+
+    let mut header: Vec<u8> = Vec::with_capacity(13);
+    header.extend_from_slice(&width.to_be_bytes());
+    header.extend_from_slice(&height.to_be_bytes());
+    // 8 bits per channel, RGBA, deflate, no filtering, not interlaced
+    header.extend_from_slice(&[8, 6, 0, 0, 0]);
+
+    let mut png: Vec<u8> = b"\x89PNG\r\n\x1a\n".to_vec();
+    for (kind, data) in [
+        (b"IHDR", header.as_slice()),
+        (b"IDAT", &zlib),
+        (b"IEND", &[]),
+    ] {
+        let mut chunk: Vec<u8> = kind.to_vec();
+        chunk.extend_from_slice(data);
+
+        png.extend_from_slice(&u32::try_from(data.len()).unwrap_or(u32::MAX).to_be_bytes());
+        png.extend_from_slice(&chunk);
+        png.extend_from_slice(&crc32(&chunk).to_be_bytes());
+    }
+
+    png
+}
@@ -1,188 +1,945 @@
 use crate::error::{Error, Kind};
-use crate::{pass, throw, Version};
-use lliw::Fg::LightYellow as Yellow;
+use crate::hash::fnv1a;
+use crate::manifest::{CompatTool, ToolManifest};
+use crate::vdf::Vdf;
+use crate::{pass, throw, xdg, Config, Flavour, RunTimeVersion, Version};
+use lliw::Fg::{Blue, LightYellow as Yellow};
 use lliw::Reset;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::ffi::OsString;
 use std::fmt::{Display, Formatter};
-use std::fs::{DirEntry, File, OpenOptions};
-use std::io::{Read, Write};
+use std::fs::{DirEntry, ReadDir};
 use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
-/// Index type to Index Proton versions in common
+/// Magic bytes at the start of the index cache
+const CACHE_MAGIC: &[u8; 8] = b"PCINDEX\0";
+
+/// Version of the index cache format, bumped whenever `Index` changes
+const CACHE_VERSION: u32 = 2;
+
+/// Length of the magic bytes, version and checksum before the cached index
+const CACHE_HEADER_LEN: usize = 20;
+
+/// Sorts entries kept by name
+fn sorted(entries: &HashMap<String, IndexEntry>) -> Vec<(&String, &IndexEntry)> {
+    let mut entries: Vec<(&String, &IndexEntry)> = entries.iter().collect();
+    entries.sort_by_key(|(name, _)| *name);
+    entries
+}
+
+/// Index type to Index Proton versions in every Steam library
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Index {
+    #[serde(skip)]
+    libraries: Vec<Library>,
+    #[serde(skip)]
+    cache: Option<PathBuf>,
+    inner: HashMap<Version, IndexEntry>,
+    named: HashMap<String, IndexEntry>,
+    #[serde(skip)]
+    custom: HashMap<String, IndexEntry>,
+}
+
+/// Directory scanned for Proton, and the library it belongs to
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Library {
+    path: PathBuf,
     dir: PathBuf,
-    inner: HashMap<Version, PathBuf>,
+}
+
+/// Proton found while indexing
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexEntry {
+    path: PathBuf,
+    library: PathBuf,
+    name: Option<String>,
+    display_name: Option<String>,
+    require_tool_appid: Option<u32>,
+}
+
+impl IndexEntry {
+    /// Creates an entry for Proton in `path`, reading the runtime it requires from its `toolmanifest.vdf`
+    fn new(
+        path: PathBuf,
+        library: PathBuf,
+        name: Option<String>,
+        display_name: Option<String>,
+    ) -> IndexEntry {
+        let require_tool_appid: Option<u32> = match ToolManifest::open(&path) {
+            Ok(manifest) => manifest.and_then(|m| m.require_tool_appid),
+            Err(e) => {
+                eprintln!("{Yellow}warning:{Reset} {e}");
+                None
+            }
+        };
+
+        IndexEntry {
+            path,
+            library,
+            name,
+            display_name,
+            require_tool_appid,
+        }
+    }
+
+    /// Returns true if `name` is the internal or display name of the tool
+    fn is_named(&self, name: &str) -> bool {
+        [&self.name, &self.display_name]
+            .iter()
+            .any(|n| n.as_deref() == Some(name))
+    }
+
+    #[must_use]
+    #[inline]
+    /// Returns the directory containing Proton
+    pub fn path(&self) -> PathBuf {
+        self.path.clone()
+    }
+
+    #[must_use]
+    #[inline]
+    /// Returns the Steam library, or tool directory, Proton was found in
+    pub fn library(&self) -> PathBuf {
+        self.library.clone()
+    }
+
+    #[must_use]
+    #[inline]
+    /// Returns the internal name declared by a compatibility tool, or the name it was added as
+    pub fn name(&self) -> Option<String> {
+        self.name.clone()
+    }
+
+    #[must_use]
+    #[inline]
+    /// Returns the display name declared by a compatibility tool
+    pub fn display_name(&self) -> Option<String> {
+        self.display_name.clone()
+    }
+
+    #[must_use]
+    #[inline]
+    /// Returns the App ID of the runtime required in the tool's manifest
+    pub fn require_tool_appid(&self) -> Option<u32> {
+        self.require_tool_appid
+    }
+
+    #[must_use]
+    #[inline]
+    /// Returns the Steam runtime required in the tool's manifest
+    pub fn runtime(&self) -> Option<RunTimeVersion> {
+        self.require_tool_appid.and_then(RunTimeVersion::from_appid)
+    }
+}
+
+/// Row of the listing of an index, as printed by `--index --format json|tsv`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct IndexListing {
+    version: String,
+    name: Option<String>,
+    path: PathBuf,
+    library: PathBuf,
+    flavour: Flavour,
+    runtime: Option<String>,
+    added: bool,
+}
+
+impl IndexListing {
+    /// Creates the row of `entry`, listed as `version`
+    fn new(version: String, flavour: Flavour, entry: &IndexEntry, added: bool) -> IndexListing {
+        IndexListing {
+            version,
+            name: entry.display_name.clone().or_else(|| entry.name.clone()),
+            path: entry.path.clone(),
+            library: entry.library.clone(),
+            flavour,
+            runtime: entry.runtime().map(|runtime| runtime.to_string()),
+            added,
+        }
+    }
+
+    #[must_use]
+    #[inline]
+    /// Returns the version, or the name Proton is used by with `-p` when it has no version
+    pub fn version(&self) -> String {
+        self.version.clone()
+    }
+
+    #[must_use]
+    #[inline]
+    /// Returns the display or internal name declared by a compatibility tool
+    pub fn name(&self) -> Option<String> {
+        self.name.clone()
+    }
+
+    #[must_use]
+    #[inline]
+    /// Returns the directory containing Proton
+    pub fn path(&self) -> PathBuf {
+        self.path.clone()
+    }
+
+    #[must_use]
+    #[inline]
+    /// Returns the Steam library, or tool directory, Proton was found in
+    pub fn library(&self) -> PathBuf {
+        self.library.clone()
+    }
+
+    #[must_use]
+    #[inline]
+    /// Returns the flavour of Proton
+    pub fn flavour(&self) -> Flavour {
+        self.flavour
+    }
+
+    #[must_use]
+    #[inline]
+    /// Returns the directory name of the Steam runtime Proton requires
+    pub fn runtime(&self) -> Option<String> {
+        self.runtime.clone()
+    }
+
+    #[must_use]
+    #[inline]
+    /// Returns true if Proton was added with `add` rather than indexed
+    pub fn added(&self) -> bool {
+        self.added
+    }
+}
+
+impl Display for IndexEntry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.path.display())?;
+
+        match (&self.display_name, &self.name) {
+            (Some(name), _) | (None, Some(name)) => write!(f, " ({name})"),
+            (None, None) => Ok(()),
+        }
+    }
 }
 
 impl Display for Index {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        let mut str: String = format!(
-            "Indexed Directory: {}\n\nIndexed {} Proton Versions:\n",
-            self.dir.to_string_lossy(),
-            self.len()
-        );
+        let mut str: String = String::from("Indexed Directories:\n");
+
+        for library in &self.libraries {
+            str = format!("{}\n{}", str, library.dir.display());
+        }
+
+        str = format!("{}\n\nIndexed {} Proton Versions:\n", str, self.len());
 
-        for (version, path) in &self.inner {
-            str = format!("{}\nProton {}: {}", str, version, path.display());
+        for (version, entry) in self.versions() {
+            str = format!("{str}\nProton {version}: {entry}");
         }
 
-        write!(f, "{}", str)
+        for (name, entry) in sorted(&self.named) {
+            str = format!("{str}\n{name}: {entry}");
+        }
+
+        if !self.custom.is_empty() {
+            str = format!("{}\n\nAdded {} Proton Builds:\n", str, self.custom.len());
+
+            for (name, entry) in sorted(&self.custom) {
+                str = format!("{str}\n{name}: {entry}");
+            }
+        }
+
+        write!(f, "{str}")
     }
 }
 
 impl Index {
-    /// Creates an index of Proton versions in given path
+    /// Creates an index of Proton versions in `common`, every Steam library and every `compatibilitytools.d`.
+    /// The index is kept in the cache from the config, or built in memory if the cache is disabled
     ///
     /// # Errors
     ///
-    /// Will fail if Indexing fails to read the directory
-    pub fn new(index: &Path) -> Result<Index, Error> {
+    /// Will fail if...
+    /// * Indexing fails to read every directory
+    /// * The cache or state location can not be found, the state location only when the cache is
+    ///   enabled
+    /// * The Proton builds added with `add` can not be read
+    pub fn new(config: &Config) -> Result<Index, Error> {
         let mut idx = Index {
-            dir: index.to_path_buf(),
+            libraries: Index::libraries(config),
+            cache: config.cache()?,
             inner: HashMap::new(),
+            named: HashMap::new(),
+            custom: HashMap::new(),
         };
 
-        idx.load()?;
+        // without a cache nothing else needs `HOME`, so builds added with `add` are skipped when
+        // the state location can not be found
+        if idx.cache.is_some() || xdg::state_home().is_ok() {
+            idx.load_builds()?;
+        }
+
+        if idx.cache.is_some() {
+            idx.load()?;
+        } else {
+            idx.scan()?;
+        }
+
         Ok(idx)
     }
 
+    #[must_use]
+    /// Lists the directories indexed with `config`: `common`, then every Steam library and every
+    /// `compatibilitytools.d`
+    pub fn directories(config: &Config) -> Vec<PathBuf> {
+        Index::libraries(config)
+            .into_iter()
+            .map(|library| library.dir)
+            .collect()
+    }
+
+    /// Lists the directories to index, `common` from the config comes first
+    fn libraries(config: &Config) -> Vec<Library> {
+        let steam: PathBuf = config.steam();
+        let common: PathBuf = config.common();
+
+        let mut libraries: Vec<Library> = vec![Library {
+            path: common
+                .parent()
+                .and_then(Path::parent)
+                .map_or_else(|| common.clone(), Path::to_path_buf),
+            dir: common,
+        }];
+
+        let folders: PathBuf = steam.join("steamapps/libraryfolders.vdf");
+
+        if folders.exists() {
+            match Vdf::open(&folders) {
+                Ok(vdf) => {
+                    let root: &Vdf = vdf.get("libraryfolders").unwrap_or(&vdf);
+
+                    for (_, folder) in root.entries() {
+                        // older files list paths directly, newer ones in a `path` key
+                        let path: Option<&str> = folder
+                            .as_str()
+                            .or_else(|| folder.get("path").and_then(Vdf::as_str));
+
+                        if let Some(path) = path {
+                            let path: PathBuf = PathBuf::from(path);
+                            libraries.insert(
+                                libraries.len(),
+                                Library {
+                                    dir: path.join("steamapps/common"),
+                                    path,
+                                },
+                            );
+                        }
+                    }
+                }
+                Err(e) => eprintln!("{Yellow}warning:{Reset} {e}"),
+            }
+        }
+
+        let mut tools: Vec<PathBuf> = vec![steam.join("compatibilitytools.d")];
+
+        if let Ok(home) = std::env::var("HOME") {
+            tools.insert(
+                tools.len(),
+                PathBuf::from(format!("{home}/.steam/root/compatibilitytools.d")),
+            );
+        }
+
+        if let Ok(extra) = std::env::var("STEAM_EXTRA_COMPAT_TOOLS_PATHS") {
+            tools.extend(
+                extra
+                    .split(':')
+                    .filter(|p| !p.is_empty())
+                    .map(PathBuf::from),
+            );
+        }
+
+        tools.insert(
+            tools.len(),
+            PathBuf::from("/usr/local/share/steam/compatibilitytools.d"),
+        );
+        tools.insert(
+            tools.len(),
+            PathBuf::from("/usr/share/steam/compatibilitytools.d"),
+        );
+
+        for dir in tools {
+            libraries.insert(
+                libraries.len(),
+                Library {
+                    path: dir.clone(),
+                    dir,
+                },
+            );
+        }
+
+        let mut seen: Vec<PathBuf> = Vec::new();
+        libraries.retain(|library| {
+            let dir: PathBuf = library
+                .dir
+                .canonicalize()
+                .unwrap_or_else(|_| library.dir.clone());
+
+            if seen.contains(&dir) {
+                false
+            } else {
+                seen.insert(seen.len(), dir);
+                true
+            }
+        });
+
+        libraries
+    }
+
     #[must_use]
     #[inline]
     /// Returns the number of Indexed Protons
     pub fn len(&self) -> usize {
-        self.inner.len()
+        self.inner.len() + self.named.len()
     }
 
     #[must_use]
     #[inline]
     /// Returns true if Index is empty
     pub fn is_empty(&self) -> bool {
-        self.inner.is_empty()
+        self.inner.is_empty() && self.named.is_empty()
     }
 
     #[must_use]
     #[inline]
     /// Retrieves the path of the requested Proton version
     pub fn get(&self, version: &Version) -> Option<PathBuf> {
-        self.inner.get(version).map(std::clone::Clone::clone)
+        self.inner.get(version).map(IndexEntry::path)
     }
 
-    fn cache_location() -> Result<PathBuf, Error> {
-        use std::env::var;
+    #[must_use]
+    #[inline]
+    /// Retrieves the index entry of the requested Proton version
+    pub fn get_entry(&self, version: &Version) -> Option<&IndexEntry> {
+        self.inner.get(version)
+    }
+
+    #[must_use]
+    /// Retrieves the newest indexed mainline Proton version and its entry, which still has its
+    /// `proton` script
+    pub fn latest(&self) -> Option<(Version, IndexEntry)> {
+        self.inner
+            .iter()
+            .filter(|(version, _)| version.flavour() == Flavour::Valve)
+            .filter(|(_, entry)| entry.path.join("proton").is_file())
+            .max_by_key(|(version, _)| **version)
+            .map(|(version, entry)| (*version, entry.clone()))
+    }
+
+    #[must_use]
+    /// Lists every indexed and added Proton in a stable order: versions from newest to oldest,
+    /// then tools without a version and then added builds, both by name
+    pub fn listing(&self) -> Vec<IndexListing> {
+        let versions = self.versions().into_iter().map(|(version, entry)| {
+            IndexListing::new(version.to_string(), version.flavour(), entry, false)
+        });
+
+        let named = sorted(&self.named)
+            .into_iter()
+            .map(|(name, entry)| (name, entry, false));
+
+        let custom = sorted(&self.custom)
+            .into_iter()
+            .map(|(name, entry)| (name, entry, true));
+
+        versions
+            .chain(named.chain(custom).map(|(name, entry, added)| {
+                let flavour: Flavour = Version::from_custom(&entry.path).flavour();
+                IndexListing::new(name.clone(), flavour, entry, added)
+            }))
+            .collect()
+    }
 
-        if let Ok(val) = var("HOME") {
-            let path = format!("{}/.cache/proton/index", val);
-            return Ok(PathBuf::from(path));
+    /// Returns the indexed versions from newest to oldest
+    fn versions(&self) -> Vec<(&Version, &IndexEntry)> {
+        let mut versions: Vec<(&Version, &IndexEntry)> = self.inner.iter().collect();
+        versions.sort_by(|(a, _), (b, _)| b.cmp(a));
+        versions
+    }
+
+    #[must_use]
+    /// Retrieves a Proton build added as `name`, indexed under a directory `name`
+    /// which is not a version, or a compatibility tool declaring `name`
+    pub fn get_named(&self, name: &str) -> Option<&IndexEntry> {
+        self.custom
+            .get(name)
+            .or_else(|| self.named.get(name))
+            .or_else(|| {
+                self.inner
+                    .values()
+                    .chain(self.named.values())
+                    .find(|entry| entry.is_named(name))
+            })
+    }
+
+    /// Adds a Proton build outside of common to the index under `name`
+    ///
+    /// # Errors
+    ///
+    /// Will fail if...
+    /// * `path` does not contain a `proton` script
+    /// * The added builds can not be saved
+    pub fn add(&mut self, name: &str, path: &Path) -> Result<PathBuf, Error> {
+        if !path.join("proton").is_file() {
+            throw!(
+                Kind::ProtonMissing,
+                "'{}' does not contain a proton script",
+                path.display()
+            );
         }
 
-        throw!(Kind::Environment, "$HOME does not exist")
+        let path: PathBuf = match path.canonicalize() {
+            Ok(p) => p,
+            Err(e) => throw!(Kind::ProtonMissing, "'{}': {}", path.display(), e),
+        };
+
+        let library: PathBuf = path.parent().unwrap_or(&path).to_path_buf();
+        let entry: IndexEntry =
+            IndexEntry::new(path.clone(), library, Some(name.to_string()), None);
+
+        self.custom.insert(name.to_string(), entry);
+        self.save_builds()?;
+
+        pass!(path)
+    }
+
+    /// Removes a Proton build previously added under `name`
+    ///
+    /// # Errors
+    ///
+    /// Will fail if...
+    /// * No Proton build was added under `name`
+    /// * The added builds can not be saved
+    pub fn remove(&mut self, name: &str) -> Result<PathBuf, Error> {
+        let path: PathBuf = match self.custom.remove(name) {
+            Some(entry) => entry.path,
+            None => throw!(Kind::ProtonMissing, "no Proton build added as '{}'", name),
+        };
+
+        self.save_builds()?;
+
+        pass!(path)
+    }
+
+    /// Returns the file Proton builds added with `add` are kept in, which outlives the cache
+    fn builds_location() -> Result<PathBuf, Error> {
+        pass!(xdg::state_home()?.join("proton/builds.toml"))
+    }
+
+    /// Loads the Proton builds added with `add`
+    fn load_builds(&mut self) -> Result<(), Error> {
+        let path: PathBuf = Self::builds_location()?;
+
+        if !path.exists() {
+            return pass!();
+        }
+
+        let builds: BTreeMap<String, PathBuf> = match std::fs::read_to_string(&path) {
+            Ok(s) => match toml::from_str(&s) {
+                Ok(builds) => builds,
+                Err(e) => throw!(Kind::IndexState, "'{}': {}", path.display(), e),
+            },
+            Err(e) => throw!(Kind::IndexState, "'{}': {}", path.display(), e),
+        };
+
+        for (name, path) in builds {
+            let library: PathBuf = path.parent().unwrap_or(&path).to_path_buf();
+            let entry: IndexEntry = IndexEntry::new(path, library, Some(name.clone()), None);
+            self.custom.insert(name, entry);
+        }
+
+        pass!()
     }
 
-    fn open_cache() -> Result<File, Error> {
-        let path: PathBuf = Self::cache_location()?;
+    /// Saves the Proton builds added with `add`
+    fn save_builds(&self) -> Result<(), Error> {
+        let path: PathBuf = Self::builds_location()?;
+
+        let builds: BTreeMap<&String, &PathBuf> = self
+            .custom
+            .iter()
+            .map(|(name, entry)| (name, &entry.path))
+            .collect();
+
+        let s: String = match toml::to_string(&builds) {
+            Ok(s) => s,
+            Err(e) => throw!(Kind::IndexState, "{}", e),
+        };
 
         if let Some(parent) = path.parent() {
-            if !parent.exists() {
-                if let Err(e) = std::fs::create_dir(parent) {
-                    throw!(Kind::IndexCache, "{}", e);
-                }
+            if let Err(e) = std::fs::create_dir_all(parent) {
+                throw!(Kind::IndexState, "'{}': {}", parent.display(), e);
             }
         }
 
-        match OpenOptions::new()
-            .read(true)
-            .write(true)
-            .create(true)
-            .open(path)
-        {
-            Ok(cache) => pass!(cache),
-            Err(e) => throw!(Kind::IndexCache, "{}", e),
+        if let Err(e) = std::fs::write(&path, s) {
+            throw!(Kind::IndexState, "'{}': {}", path.display(), e);
         }
+
+        pass!()
     }
 
-    fn load(&mut self) -> Result<(), Error> {
-        if let Err(e) = self._load() {
-            eprintln!("{}warning{}: {}\nreindexing...", Yellow, Reset, e);
-            self.index()?;
+    /// Returns the modification time of every indexed directory, which changes when
+    /// a Proton directory is added to or removed from it
+    fn mtimes(&self) -> Vec<(PathBuf, Option<SystemTime>)> {
+        self.libraries
+            .iter()
+            .map(|library| {
+                let mtime: Option<SystemTime> =
+                    library.dir.metadata().and_then(|m| m.modified()).ok();
+                (library.dir.clone(), mtime)
+            })
+            .collect()
+    }
 
-            if let Err(e) = self.save() {
-                eprintln!("{}warning:{} {}\n", Yellow, Reset, e);
+    fn load(&mut self) -> Result<(), Error> {
+        match self.try_load() {
+            Ok(true) => {}
+            Ok(false) => {
+                eprintln!("{Blue}info:{Reset} Steam libraries changed, reindexing...");
+                self.index()?;
+            }
+            Err(e) => {
+                eprintln!("{Yellow}warning{Reset}: {e}\nreindexing...");
+                self.index()?;
             }
         }
 
         Ok(())
     }
 
-    fn _load(&mut self) -> Result<(), Error> {
-        let cache: File = Self::open_cache()?;
-        self.read_index(cache)?;
-        Ok(())
-    }
+    /// Loads the cache, returns false if an indexed directory changed since it was saved
+    fn try_load(&mut self) -> Result<bool, Error> {
+        let path: &Path = match &self.cache {
+            Some(path) => path,
+            None => return pass!(false),
+        };
 
-    fn read_index(&mut self, mut f: File) -> Result<(), Error> {
-        let mut buf: Vec<u8> = Vec::new();
+        let buf: Vec<u8> = match std::fs::read(path) {
+            Ok(buf) => buf,
+            Err(e) => throw!(Kind::IndexCache, "{}", e),
+        };
 
-        if let Err(e) = f.read_to_end(&mut buf) {
-            throw!(Kind::IndexCache, "{}", e);
-        }
+        let body: &[u8] = match buf.get(..CACHE_HEADER_LEN) {
+            Some(header) if header[..8] == CACHE_MAGIC[..] => {
+                let version: [u8; 4] = header[8..12].try_into().unwrap_or_default();
+                let checksum: [u8; 8] = header[12..20].try_into().unwrap_or_default();
 
-        self.inner = match bincode::deserialize::<Self>(&buf) {
-            Ok(c) => c.inner,
-            Err(_) => throw!(Kind::IndexCache, "can't deserialize"),
+                if u32::from_le_bytes(version) != CACHE_VERSION {
+                    throw!(Kind::IndexCache, "cache version is not {}", CACHE_VERSION);
+                }
+
+                let body: &[u8] = &buf[CACHE_HEADER_LEN..];
+
+                if u64::from_le_bytes(checksum) != fnv1a(body) {
+                    throw!(Kind::IndexCache, "cache is corrupt");
+                }
+
+                body
+            }
+            _ => throw!(Kind::IndexCache, "not an index cache"),
         };
 
-        Ok(())
+        let (mtimes, cache): (Vec<(PathBuf, Option<SystemTime>)>, Self) =
+            match bincode::deserialize(body) {
+                Ok(c) => c,
+                Err(_) => throw!(Kind::IndexCache, "can't deserialize"),
+            };
+
+        self.inner = cache.inner;
+        self.named = cache.named;
+
+        Ok(mtimes == self.mtimes())
     }
 
+    /// Saves the cache, writing to a temporary file first so the cache is never left half written.
+    /// Nothing is saved if the cache is disabled
     fn save(&self) -> Result<(), Error> {
-        let mut cache: File = Self::open_cache()?;
+        let path: &Path = match &self.cache {
+            Some(path) => path,
+            None => return pass!(),
+        };
+
+        if let Some(parent) = path.parent() {
+            if let Err(e) = std::fs::create_dir_all(parent) {
+                throw!(Kind::IndexCache, "'{}': {}", parent.display(), e);
+            }
+        }
 
-        let bytes: Vec<u8> = match bincode::serialize(self) {
+        let body: Vec<u8> = match bincode::serialize(&(self.mtimes(), self)) {
             Ok(b) => b,
             Err(e) => throw!(Kind::IndexCache, "{}", e),
         };
 
-        if let Err(e) = cache.write(&bytes) {
+        let mut bytes: Vec<u8> = Vec::with_capacity(CACHE_HEADER_LEN + body.len());
+        bytes.extend_from_slice(CACHE_MAGIC);
+        bytes.extend_from_slice(&CACHE_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&fnv1a(&body).to_le_bytes());
+        bytes.extend_from_slice(&body);
+
+        let tmp: PathBuf = path.with_extension("tmp");
+
+        if let Err(e) = std::fs::write(&tmp, &bytes) {
+            throw!(Kind::IndexCache, "{}", e);
+        }
+
+        if let Err(e) = std::fs::rename(&tmp, path) {
             throw!(Kind::IndexCache, "{}", e);
         }
 
         Ok(())
     }
 
-    // TODO: Refactor and optimize `index` method.
-
-    /// Indexes Proton versions
+    /// Indexes Proton versions in every library, earlier libraries take precedence, and saves the cache if enabled
     /// # Errors
-    /// An error is returned when the function cannot read the common directory
+    /// An error is returned when the function cannot read any of the libraries
     pub fn index(&mut self) -> Result<(), Error> {
-        if let Ok(rd) = self.dir.read_dir() {
-            for result_entry in rd {
-                let entry: DirEntry = if let Ok(e) = result_entry {
-                    e
-                } else {
-                    eprintln!("{}warning:{} failed indexing a directory...", Yellow, Reset);
-                    continue;
-                };
+        self.scan()?;
+
+        if let Err(e) = self.save() {
+            eprintln!("{Yellow}warning:{Reset} {e}\n");
+        }
 
-                let entry_path: PathBuf = entry.path();
+        pass!()
+    }
 
-                if entry_path.is_dir() {
-                    let name: OsString = entry.file_name();
-                    let name: String = name.to_string_lossy().to_string();
-                    if let Some(version_str) = name.split(' ').last() {
-                        if let Ok(version) = version_str.parse() {
-                            self.inner.insert(version, entry_path);
-                        }
+    fn scan(&mut self) -> Result<(), Error> {
+        self.inner.clear();
+        self.named.clear();
+
+        let mut read: bool = false;
+
+        for library in self.libraries.clone() {
+            if !library.dir.exists() {
+                continue;
+            }
+
+            match self.index_library(&library) {
+                Ok(()) => read = true,
+                Err(e) => eprintln!("{Yellow}warning:{Reset} {e}"),
+            }
+        }
+
+        if !read {
+            throw!(Kind::IndexReadDir, "can not read any Steam library");
+        }
+
+        pass!()
+    }
+
+    fn index_library(&mut self, library: &Library) -> Result<(), Error> {
+        let rd: ReadDir = match library.dir.read_dir() {
+            Ok(rd) => rd,
+            Err(e) => throw!(Kind::IndexReadDir, "'{}': {}", library.dir.display(), e),
+        };
+
+        for result_entry in rd {
+            let entry: DirEntry = if let Ok(e) = result_entry {
+                e
+            } else {
+                eprintln!("{Yellow}warning:{Reset} failed indexing a directory...");
+                continue;
+            };
+
+            let entry_path: PathBuf = entry.path();
+
+            if !entry_path.is_dir() {
+                continue;
+            }
+
+            let name: OsString = entry.file_name();
+            let name: String = name.to_string_lossy().to_string();
+
+            match CompatTool::open(&entry_path) {
+                Ok(Some(tools)) => {
+                    for tool in tools {
+                        self.index_tool(library, &name, tool);
                     }
+                    continue;
                 }
+                Ok(None) => {}
+                Err(e) => eprintln!("{Yellow}warning:{Reset} {e}"),
+            }
+
+            // games share `common` with Proton, only directories with its script are Proton
+            if !entry_path.join("proton").is_file() {
+                continue;
+            }
+
+            let index_entry: IndexEntry =
+                IndexEntry::new(entry_path, library.path.clone(), None, None);
+
+            if let Ok(version) = name.parse() {
+                self.inner.entry(version).or_insert(index_entry);
+            } else {
+                self.named.entry(name).or_insert(index_entry);
             }
-        } else {
-            throw!(Kind::IndexReadDir, "can not read common dir");
         }
 
         pass!()
     }
+
+    /// Indexes a tool declared in a `compatibilitytool.vdf` found in directory `dir_name`
+    fn index_tool(&mut self, library: &Library, dir_name: &str, tool: CompatTool) {
+        if !ToolManifest::runnable(&tool.path) {
+            eprintln!(
+                "{Yellow}warning:{Reset} skipping tool '{}' in '{}': no proton script or toolmanifest.vdf command to run",
+                tool.name,
+                tool.path.display()
+            );
+            return;
+        }
+
+        let version: Option<Version> = [
+            Some(dir_name),
+            Some(tool.name.as_str()),
+            tool.display_name.as_deref(),
+        ]
+        .iter()
+        .flatten()
+        .find_map(|name| name.parse().ok());
+
+        let key: String = tool.name.clone();
+        let index_entry: IndexEntry = IndexEntry::new(
+            tool.path,
+            library.path.clone(),
+            Some(tool.name),
+            tool.display_name,
+        );
+
+        if let Some(version) = version {
+            self.inner.entry(version).or_insert(index_entry);
+        } else {
+            self.named.entry(key).or_insert(index_entry);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Index, IndexEntry, Library};
+    use crate::Version;
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    /// Library in the temporary directory, removed with everything in it when dropped
+    struct TempLibrary {
+        root: PathBuf,
+    }
+
+    impl TempLibrary {
+        /// Creates a library holding the directories `names` in its `common`, those in `protons`
+        /// with a `proton` script
+        fn new(test: &str, names: &[&str], protons: &[&str]) -> TempLibrary {
+            let library: TempLibrary = TempLibrary {
+                root: std::env::temp_dir()
+                    .join(format!("proton-call-{}-{test}", std::process::id())),
+            };
+            let common: PathBuf = library.common();
+
+            for name in names {
+                std::fs::create_dir_all(common.join(name)).unwrap();
+            }
+
+            for name in protons {
+                std::fs::create_dir_all(common.join(name)).unwrap();
+                std::fs::write(common.join(name).join("proton"), "").unwrap();
+            }
+
+            library
+        }
+
+        fn common(&self) -> PathBuf {
+            self.root.join("steamapps/common")
+        }
+
+        fn index(&self) -> Index {
+            let mut index: Index = Index {
+                libraries: vec![Library {
+                    path: self.root.clone(),
+                    dir: self.common(),
+                }],
+                cache: None,
+                inner: HashMap::new(),
+                named: HashMap::new(),
+                custom: HashMap::new(),
+            };
+
+            index.scan().unwrap();
+            index
+        }
+    }
+
+    impl Drop for TempLibrary {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.root);
+        }
+    }
+
+    #[test]
+    fn latest_skips_games() {
+        let library: TempLibrary = TempLibrary::new(
+            "latest",
+            &["911 Operator", "7 Days to Die", "60 Seconds!", "8.0"],
+            &["Proton 7.0", "Proton 8.0"],
+        );
+        let common: PathBuf = library.common();
+        let mut index: Index = library.index();
+
+        assert_eq!(index.len(), 2);
+        assert_eq!(
+            index.get(&Version::new(8, 0)),
+            Some(common.join("Proton 8.0"))
+        );
+
+        // a game left in an old cache
+        index.inner.insert(
+            Version::new(911, 0),
+            IndexEntry::new(common.join("911 Operator"), common.clone(), None, None),
+        );
+
+        assert_eq!(
+            index.latest().map(|(version, _)| version),
+            Some(Version::new(8, 0))
+        );
+    }
+
+    #[test]
+    fn indexes_tools_run_by_their_manifest() {
+        let library: TempLibrary = TempLibrary::new("tools", &["luxtorpeda", "broken"], &[]);
+        let common: PathBuf = library.common();
+
+        for name in ["luxtorpeda", "broken"] {
+            std::fs::write(
+                common.join(name).join("compatibilitytool.vdf"),
+                format!(
+                    "\"compatibilitytools\" {{ \"compat_tools\" {{ \"{name}\" {{ \"install_path\" \".\" }} }} }}"
+                ),
+            )
+            .unwrap();
+            std::fs::write(
+                common.join(name).join("toolmanifest.vdf"),
+                "\"manifest\" { \"commandline\" \"/luxtorpeda.sh %verb%\" }",
+            )
+            .unwrap();
+        }
+
+        // only `luxtorpeda` has the script its manifest runs
+        std::fs::write(common.join("luxtorpeda/luxtorpeda.sh"), "").unwrap();
+
+        let index: Index = library.index();
+
+        assert_eq!(
+            index.get_named("luxtorpeda").map(IndexEntry::path),
+            Some(common.join("luxtorpeda/."))
+        );
+        assert!(index.get_named("broken").is_none());
+    }
 }
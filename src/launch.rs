@@ -0,0 +1,229 @@
+use crate::error::{Error, Kind};
+use crate::runtime::Runtime;
+use crate::{pass, throw, Proton, Verb};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+use std::process::{Child, Command, ExitStatus, Output, Stdio};
+
+/// Plan of a launch: the final command line, environment and working directory
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Launch {
+    verb: Verb,
+    wrappers: Vec<Vec<String>>,
+    command: Vec<String>,
+    env: BTreeMap<String, String>,
+    unset_env: Vec<String>,
+    dir: Option<PathBuf>,
+}
+
+impl Launch {
+    #[must_use]
+    /// Plans the launch of `proton`, wrapped in `runtime` when given. Unless another verb is given,
+    /// a launch in a runtime waits for the prefix with `waitforexitandrun` as Steam does, and `run` otherwise
+    pub fn from_proton(proton: &Proton, runtime: Option<&Runtime>) -> Launch {
+        let verb: Verb = match (proton.verb, runtime) {
+            (Some(verb), _) => verb,
+            (None, Some(_)) => Verb::WaitForExitAndRun,
+            (None, None) => Verb::Run,
+        };
+
+        let wrappers: Vec<Vec<String>> = match runtime {
+            Some(runtime) => vec![runtime.command(verb)],
+            None => Vec::new(),
+        };
+
+        let mut command: Vec<String> = proton.command(verb.name());
+        command.insert(command.len(), proton.program.to_string_lossy().to_string());
+        command.extend(proton.args.iter().cloned());
+
+        let mut env: BTreeMap<String, String> = BTreeMap::new();
+        env.insert(
+            "STEAM_COMPAT_DATA_PATH".to_string(),
+            proton.compat.to_string_lossy().to_string(),
+        );
+        env.insert(
+            "STEAM_COMPAT_CLIENT_INSTALL_PATH".to_string(),
+            proton.steam.to_string_lossy().to_string(),
+        );
+
+        for key in &proton.unset_env {
+            env.remove(key);
+        }
+
+        // leaves out unset variables itself, other than overrides merged into them
+        env.extend(proton.gen_options());
+
+        Launch {
+            verb,
+            wrappers,
+            command,
+            env,
+            unset_env: proton.unset_env.clone(),
+            dir: proton.dir.clone(),
+        }
+    }
+
+    #[must_use]
+    /// Returns the full command line, the wrappers followed by the command
+    pub fn argv(&self) -> Vec<String> {
+        self.wrappers
+            .iter()
+            .flatten()
+            .chain(&self.command)
+            .cloned()
+            .collect()
+    }
+
+    #[must_use]
+    #[inline]
+    /// Returns the verb Proton and the runtime are run with
+    pub fn verb(&self) -> Verb {
+        self.verb
+    }
+
+    #[must_use]
+    #[inline]
+    /// Returns the command lines the command is run in, such as the Steam runtime's, outermost first
+    pub fn wrappers(&self) -> Vec<Vec<String>> {
+        self.wrappers.clone()
+    }
+
+    #[must_use]
+    #[inline]
+    /// Returns the command line of Proton, the program and its arguments
+    pub fn command(&self) -> Vec<String> {
+        self.command.clone()
+    }
+
+    #[must_use]
+    #[inline]
+    /// Returns the environment variables set for the launch
+    pub fn env(&self) -> BTreeMap<String, String> {
+        self.env.clone()
+    }
+
+    #[must_use]
+    #[inline]
+    /// Returns the environment variables removed for the launch
+    pub fn unset_env(&self) -> Vec<String> {
+        self.unset_env.clone()
+    }
+
+    #[must_use]
+    #[inline]
+    /// Returns the working directory of the launch
+    pub fn dir(&self) -> Option<PathBuf> {
+        self.dir.clone()
+    }
+
+    /// Builds the command to spawn
+    fn build(&self) -> Result<Command, Error> {
+        let line: Vec<String> = self.argv();
+
+        let (program, args): (&String, &[String]) = match line.split_first() {
+            Some(split) => split,
+            None => throw!(Kind::Internal, "empty command line"),
+        };
+
+        let mut command: Command = Command::new(program);
+        command.args(args);
+
+        // removed before the environment is set, which may give some of them new values
+        for key in &self.unset_env {
+            command.env_remove(key);
+        }
+
+        command.envs(&self.env);
+
+        if let Some(dir) = &self.dir {
+            command.current_dir(dir);
+        }
+
+        pass!(command)
+    }
+
+    /// Spawns the command and waits for it to exit
+    ///
+    /// # Errors
+    ///
+    /// Will fail if the command fails to spawn, or waiting for it fails
+    pub fn execute(&self) -> Result<ExitStatus, Error> {
+        let mut command: Command = self.build()?;
+
+        let mut child: Child = match command.spawn() {
+            Ok(c) => c,
+            Err(e) => throw!(Kind::ProtonSpawn, "{}\nDebug:\n{:#?}", e, self),
+        };
+
+        let status: ExitStatus = match child.wait() {
+            Ok(e) => e,
+            Err(e) => throw!(Kind::ProtonWait, "'{}': {}", child.id(), e),
+        };
+
+        pass!(status)
+    }
+
+    /// Spawns the command and returns what it prints, without the trailing newline, such as the
+    /// path printed by `getcompatpath`
+    ///
+    /// # Errors
+    ///
+    /// Will fail if the command fails to spawn, waiting for it fails, or it exits unsuccessfully
+    pub fn output(&self) -> Result<String, Error> {
+        let mut command: Command = self.build()?;
+        command.stdin(Stdio::null()).stderr(Stdio::inherit());
+
+        let output: Output = match command.output() {
+            Ok(o) => o,
+            Err(e) => throw!(Kind::ProtonSpawn, "{}\nDebug:\n{:#?}", e, self),
+        };
+
+        if !output.status.success() {
+            match output.status.code() {
+                Some(code) => throw!(Kind::ProtonExit, "code: {}", code),
+                None => throw!(Kind::ProtonExit, "an error"),
+            }
+        }
+
+        let stdout: String = String::from_utf8_lossy(&output.stdout).to_string();
+        pass!(stdout.trim_end_matches(['\r', '\n']).to_string())
+    }
+}
+
+/// Quotes `s` for a POSIX shell, if it contains anything but safe characters
+fn quote(s: &str) -> String {
+    let safe = |c: char| c.is_ascii_alphanumeric() || "@%+=:,./_-".contains(c);
+
+    if !s.is_empty() && s.chars().all(safe) {
+        s.to_string()
+    } else {
+        format!("'{}'", s.replace('\'', "'\\''"))
+    }
+}
+
+impl Display for Launch {
+    /// Writes the launch as a shell command line
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if let Some(dir) = &self.dir {
+            write!(f, "cd {} && ", quote(&dir.to_string_lossy()))?;
+        }
+
+        write!(f, "env")?;
+
+        for key in &self.unset_env {
+            write!(f, " -u {}", quote(key))?;
+        }
+
+        for (key, value) in &self.env {
+            write!(f, " {}", quote(&format!("{key}={value}")))?;
+        }
+
+        for arg in self.argv() {
+            write!(f, " {}", quote(&arg))?;
+        }
+
+        Ok(())
+    }
+}
@@ -1,7 +1,6 @@
 #![forbid(unsafe_code)]
 #![forbid(missing_docs)]
 #![forbid(unstable_features)]
-#![forbid(missing_fragment_specifier)]
 #![warn(clippy::all, clippy::pedantic)]
 
 /*!
@@ -11,26 +10,56 @@ This defines the internal API used in `proton-call` to run Proton
 */
 
 mod config;
+mod dll;
+mod hash;
 mod index;
+mod launch;
+mod manifest;
+mod pe;
+mod profile;
+mod registry;
 mod runtime;
 mod runtime_options;
+mod tool;
+mod vdf;
+mod verb;
 mod version;
+mod winetricks;
 
+/// Generates freedesktop launchers for the games of the config
+pub mod desktop;
 /// Contains the `Error` and `ErrorKind` types
 pub mod error;
+/// Extracts the icons of Windows programs into the hicolor icon theme
+pub mod icon;
+/// Names and manages the Wine prefixes kept in the data directory
+pub mod prefix;
+/// Reads and writes the non-Steam games in Steam's library
+pub mod shortcuts;
+/// Resolves the XDG base directories for config, cache, data and state
+pub mod xdg;
 
 pub use config::Config;
+pub use dll::{DllOverride, LoadOrder};
 use error::{Error, Kind};
-pub use index::Index;
+pub use index::{Index, IndexEntry, IndexListing};
+pub use launch::Launch;
+pub use profile::Profile;
 pub use runtime::RunTimeVersion;
 use runtime::Runtime;
-pub use runtime_options::RuntimeOption;
+pub use runtime_options::{RuntimeOption, RuntimeSetting};
 use std::borrow::Cow;
 use std::fs::create_dir;
-pub use version::Version;
+pub use tool::Tool;
+pub use verb::Verb;
+pub use version::{Flavour, Version};
 
-use std::path::PathBuf;
+use manifest::{ToolManifest, PROTON_COMMANDLINE};
+use prefix::{Prefix, PrefixMeta};
+use registry::RegFile;
+use std::path::{Path, PathBuf};
 use std::process::ExitStatus;
+use winetricks::Winetricks;
 
 /// Type to handle executing Proton
 #[derive(Debug)]
@@ -39,22 +68,34 @@ pub struct Proton {
     path: PathBuf,
     program: PathBuf,
     args: Vec<String>,
-    options: Vec<RuntimeOption>,
+    options: Vec<RuntimeSetting>,
     compat: PathBuf,
     steam: PathBuf,
     runtime: Option<RunTimeVersion>,
     common: PathBuf,
+    libraries: Vec<PathBuf>,
+    env: Vec<(String, String)>,
+    unset_env: Vec<String>,
+    dir: Option<PathBuf>,
+    prefix: Option<String>,
+    tool: Option<Tool>,
+    verb: Option<Verb>,
+    winetricks: Vec<String>,
+    registry: Vec<PathBuf>,
+    dlls: Vec<DllOverride>,
+    manifest: ToolManifest,
 }
 
 impl Proton {
     #[must_use]
+    #[allow(clippy::too_many_arguments)]
     /// Creates a new instance of `Proton`
     pub fn new(
         version: Version,
         path: PathBuf,
         program: PathBuf,
         args: Vec<String>,
-        options: Vec<RuntimeOption>,
+        options: Vec<RuntimeSetting>,
         compat: PathBuf,
         steam: PathBuf,
         runtime: Option<RunTimeVersion>,
@@ -70,35 +111,166 @@ impl Proton {
             steam,
             runtime,
             common,
+            libraries: Vec::new(),
+            env: Vec::new(),
+            unset_env: Vec::new(),
+            dir: None,
+            prefix: None,
+            tool: None,
+            verb: None,
+            winetricks: Vec::new(),
+            registry: Vec::new(),
+            dlls: Vec::new(),
+            manifest: ToolManifest::default(),
         }
         .update_path()
     }
 
+    #[must_use]
+    /// Sets extra environment variables passed to Proton
+    pub fn with_env(mut self, env: Vec<(String, String)>) -> Proton {
+        self.env = env;
+        self
+    }
+
+    #[must_use]
+    /// Sets environment variables removed from the environment Proton is started in
+    pub fn with_unset_env(mut self, unset_env: Vec<String>) -> Proton {
+        self.unset_env = unset_env;
+        self
+    }
+
+    #[must_use]
+    /// Sets the working directory Proton is started in
+    pub fn with_dir(mut self, dir: Option<PathBuf>) -> Proton {
+        self.dir = dir;
+        self
+    }
+
+    #[must_use]
+    /// Sets the prefix Proton runs in, instead of the prefix of its version
+    pub fn with_prefix(mut self, prefix: Option<String>) -> Proton {
+        self.prefix = prefix;
+        self
+    }
+
+    #[must_use]
+    /// Sets the Wine tool run in place of the program, given the same arguments
+    pub fn with_tool(mut self, tool: Option<Tool>) -> Proton {
+        if let Some(tool) = tool {
+            self.program = PathBuf::from(tool.exe());
+        }
+
+        self.tool = tool;
+        self
+    }
+
+    #[must_use]
+    /// Sets the verb Proton is run with, instead of `waitforexitandrun` in a runtime and `run` otherwise
+    pub fn with_verb(mut self, verb: Option<Verb>) -> Proton {
+        self.verb = verb;
+        self
+    }
+
+    #[must_use]
+    /// Sets the winetricks verbs applied to the prefix before Proton runs, once per prefix
+    pub fn with_winetricks(mut self, winetricks: Vec<String>) -> Proton {
+        self.winetricks = winetricks;
+        self
+    }
+
+    #[must_use]
+    /// Sets the registry files imported into the prefix before Proton runs, once per prefix
+    pub fn with_registry(mut self, registry: Vec<PathBuf>) -> Proton {
+        self.registry = registry;
+        self
+    }
+
+    #[must_use]
+    /// Sets the DLL overrides merged into `WINEDLLOVERRIDES`, later overrides of a DLL win
+    pub fn with_dlls(mut self, dlls: Vec<DllOverride>) -> Proton {
+        self.dlls = dlls;
+        self
+    }
+
+    #[must_use]
+    /// Sets the directories the Steam runtime is looked for in, such as those of the index
+    pub fn with_libraries(mut self, libraries: Vec<PathBuf>) -> Proton {
+        self.libraries = libraries;
+        self
+    }
+
+    #[must_use]
+    #[inline]
+    /// Returns the version of Proton
+    pub fn version(&self) -> Version {
+        self.version
+    }
+
+    #[must_use]
+    /// Returns the name of the prefix Proton runs in, in the data directory
+    pub fn prefix_name(&self) -> String {
+        match &self.prefix {
+            Some(prefix) => prefix.clone(),
+            None => format!("Proton {}", self.version),
+        }
+    }
+
     /// Appends the executable to the path
     fn update_path(mut self) -> Proton {
         let str: Cow<str> = self.path.to_string_lossy();
-        let str: String = format!("{}/proton", str);
+        let str: String = format!("{str}/proton");
         self.path = PathBuf::from(str);
         self
     }
 
-    fn create_p_dir(&mut self) -> Result<(), Error> {
-        let name: Cow<str> = self.compat.to_string_lossy();
-        let newdir: PathBuf = PathBuf::from(format!("{}/Proton {}", name, self.version));
+    /// Changes `compat` path to the directory of the prefix given, or else of the version of Proton in use
+    fn set_p_dir(&mut self) -> Result<(), Error> {
+        let name: String = self.prefix_name();
+        prefix::check_name(&name)?;
+
+        self.compat = self.compat.join(name);
 
-        if !newdir.exists() {
-            if let Err(e) = create_dir(&newdir) {
+        pass!()
+    }
+
+    fn create_p_dir(&self) -> Result<(), Error> {
+        if !self.compat.exists() {
+            if let Err(e) = create_dir(&self.compat) {
                 throw!(Kind::ProtonDir, "failed to create Proton directory: {}", e);
             }
         }
 
-        self.compat = newdir;
-
         pass!()
     }
 
+    /// Records the version of Proton in the metadata of the prefix in use
+    fn record_version(&self) -> Result<(), Error> {
+        let data: &Path = self.compat.parent().unwrap_or(&self.compat);
+        let prefix: Prefix = Prefix::open(data, &self.prefix_name())?;
+
+        let mut meta: PrefixMeta = PrefixMeta::open(&prefix)?;
+        meta.record(self.version, None);
+        meta.save(&prefix)
+    }
+
+    /// Returns the directory containing Proton
+    fn tool_dir(&self) -> &Path {
+        self.path.parent().unwrap_or(&self.path)
+    }
+
+    /// Builds the command line to run Proton with `verb`, as declared in its `toolmanifest.vdf`
+    fn command(&self, verb: &str) -> Vec<String> {
+        self.manifest
+            .command(self.tool_dir(), verb, PROTON_COMMANDLINE)
+    }
+
+    /// Checks the command Proton is run with exists, tools such as Luxtorpeda are run by the
+    /// command of their `toolmanifest.vdf` instead of a `proton` script
     fn check_proton(&self) -> Result<(), Error> {
-        if !self.path.exists() {
+        let command: Vec<String> = self.command("run");
+
+        if !command.first().is_some_and(|exe| Path::new(exe).is_file()) {
             throw!(Kind::ProtonMissing, "{}", self.version);
         }
 
@@ -106,88 +278,271 @@ impl Proton {
     }
 
     fn check_program(&self) -> Result<(), Error> {
-        if !self.program.exists() {
+        // tools are found by Wine in the prefix, and paths to convert may only exist in it
+        let in_prefix: bool = self.tool.is_some() || self.verb.is_some_and(Verb::converts_path);
+
+        if !in_prefix && !self.program.exists() {
             throw!(Kind::ProgramMissing, "{}", self.program.to_string_lossy());
         }
 
         pass!()
     }
 
+    /// Generates the environment for Proton, later variables replace earlier ones: runtime options,
+    /// then the extra environment in the order it was given. Unset variables are left out. DLL
+    /// overrides are merged into `WINEDLLOVERRIDES` from the extra environment, or else inherited
     fn gen_options(&self) -> Vec<(String, String)> {
-        let mut opts = Vec::new();
-        for opt in &self.options {
-            opts.insert(opts.len(), (opt.to_string(), "1".to_string()))
+        let options = self
+            .options
+            .iter()
+            .map(|opt| (opt.option().to_string(), opt.value()));
+
+        let mut opts: Vec<(String, String)> = Vec::new();
+        for (key, value) in options.chain(self.env.iter().cloned()) {
+            opts.retain(|(k, _)| *k != key);
+            opts.insert(opts.len(), (key, value));
         }
+
+        opts.retain(|(k, _)| !self.unset_env.contains(k));
+
+        if !self.dlls.is_empty() {
+            let key: &str = "WINEDLLOVERRIDES";
+
+            let base: Option<String> = match opts.iter().position(|(k, _)| k == key) {
+                Some(i) => Some(opts.remove(i).1),
+                None if self.unset_env.iter().any(|k| k == key) => None,
+                None => std::env::var(key).ok(),
+            };
+
+            let value: String = dll::merge(base.as_deref().unwrap_or_default(), &self.dlls);
+            opts.insert(opts.len(), (key.to_string(), value));
+        }
+
         opts
     }
 
-    /// Changes `compat` path to the version of Proton in use, creates the directory if doesn't already exist
+    /// Checks Proton and the program exist, and finds the runtime Proton is run in:
+    /// the runtime given, or the one required in its `toolmanifest.vdf`
+    fn prepare(&mut self) -> Result<Option<Runtime>, Error> {
+        let runtime: Option<Runtime> = self.prepare_proton()?;
+        self.check_program()?;
+
+        pass!(runtime)
+    }
+
+    /// Checks Proton exists, and finds the runtime it is run in
+    fn prepare_proton(&mut self) -> Result<Option<Runtime>, Error> {
+        self.set_p_dir()?;
+
+        self.manifest = ToolManifest::open(self.tool_dir())?.unwrap_or_default();
+        self.check_proton()?;
+
+        Runtime::from_proton(self.runtime, self)
+    }
+
+    /// Runs `tool` with `args` through Proton in the prefix, in place of the program
+    fn run_tool(
+        &mut self,
+        runtime: Option<&Runtime>,
+        tool: Tool,
+        args: Vec<String>,
+    ) -> Result<(), Error> {
+        let program: PathBuf = std::mem::replace(&mut self.program, PathBuf::from(tool.exe()));
+        let args: Vec<String> = std::mem::replace(&mut self.args, args);
+        let saved: Option<Tool> = self.tool.replace(tool);
+        let verb: Option<Verb> = self.verb.take();
+
+        let status: Result<ExitStatus, Error> = Launch::from_proton(self, runtime).execute();
+
+        self.program = program;
+        self.args = args;
+        self.tool = saved;
+        self.verb = verb;
+
+        match status? {
+            status if status.success() => pass!(),
+            status => throw!(Kind::ProtonExit, "{}: {}", tool, status),
+        }
+    }
+
+    /// Has Proton set up the prefix, by running `wineboot` in it, if it has not been
+    fn setup_prefix(&mut self, runtime: Option<&Runtime>) -> Result<(), Error> {
+        if self.compat.join("pfx").is_dir() {
+            return pass!();
+        }
+
+        println!("Setting up prefix {}", self.prefix_name());
+        self.run_tool(runtime, Tool::Wineboot, Vec::new())
+    }
+
+    /// Imports the registry files the prefix does not have yet, or all of them if `force`,
+    /// with `regedit`, recording the hash of each in the prefix's metadata
+    fn apply_registry(
+        &mut self,
+        runtime: Option<&Runtime>,
+        force: bool,
+    ) -> Result<Vec<PathBuf>, Error> {
+        if self.registry.is_empty() {
+            return pass!(Vec::new());
+        }
+
+        // every file is checked before anything is imported
+        let files: Vec<RegFile> = self
+            .registry
+            .iter()
+            .map(|path| RegFile::open(path))
+            .collect::<Result<_, _>>()?;
+
+        self.setup_prefix(runtime)?;
+
+        let data: PathBuf = self.compat.parent().unwrap_or(&self.compat).to_path_buf();
+        let prefix: Prefix = Prefix::open(&data, &self.prefix_name())?;
+        let mut meta: PrefixMeta = PrefixMeta::open(&prefix)?;
+        let mut applied: Vec<PathBuf> = Vec::new();
+
+        for file in files {
+            if !force && meta.has_registry(&file.hash()) {
+                continue;
+            }
+
+            println!(
+                "Importing {} into prefix {}",
+                file.path().display(),
+                prefix.name()
+            );
+            self.run_tool(
+                runtime,
+                Tool::Regedit,
+                vec!["/S".to_string(), file.wine_path()],
+            )?;
+
+            // saved after every file, so those imported are kept if a later one fails
+            meta.record_registry(&file.path(), &file.hash());
+            meta.save(&prefix)?;
+            applied.insert(applied.len(), file.path());
+        }
+
+        pass!(applied)
+    }
+
+    /// Applies the winetricks verbs the prefix does not have yet, or all of them if `force`,
+    /// having Proton set up the prefix first if it has not been
+    fn apply_winetricks(
+        &mut self,
+        runtime: Option<&Runtime>,
+        force: bool,
+    ) -> Result<Vec<String>, Error> {
+        if self.winetricks.is_empty() {
+            return pass!(Vec::new());
+        }
+
+        self.setup_prefix(runtime)?;
+
+        Winetricks::from_proton(self)?.apply(&self.winetricks, force)
+    }
+
+    /// Plans the launch of Proton without running anything, nor creating the compat env directory
+    ///
+    /// # Errors
+    ///
+    /// Will fail on:
+    /// * Proton or the program not existing
+    /// * Reading Proton's `toolmanifest.vdf` fails
+    /// * The runtime is unknown or not installed
+    pub fn launch(mut self) -> Result<Launch, Error> {
+        let runtime: Option<Runtime> = self.prepare()?;
+        pass!(Launch::from_proton(&self, runtime.as_ref()))
+    }
+
+    /// Changes `compat` path to the prefix in use, creates the directory if doesn't already exist.
+    /// Proton is run in the runtime given, or the one required in its `toolmanifest.vdf`
     ///
     /// # Errors
     ///
     /// Will fail on:
     /// * Creating a Proton compat env directory fails
+    /// * Recording the version of Proton in the prefix fails
+    /// * Reading Proton's `toolmanifest.vdf` fails
+    /// * The runtime is unknown or not installed
+    /// * Applying a winetricks verb fails
+    /// * Reading or importing a registry file fails
     /// * Executing Proton fails
     pub fn run(mut self) -> Result<ExitStatus, Error> {
+        let runtime: Option<Runtime> = self.prepare()?;
         self.create_p_dir()?;
-        self.check_proton()?;
-        self.check_program()?;
+        self.record_version()?;
+        self.apply_winetricks(runtime.as_ref(), false)?;
+        self.apply_registry(runtime.as_ref(), false)?;
 
-        // check one for runtimes
-        if let Some(runtime) = self.runtime {
-            let runtime = Runtime::from_proton(runtime, self)?;
-            return runtime.execute();
+        let launch: Launch = Launch::from_proton(&self, runtime.as_ref());
+
+        if runtime.is_none() {
+            println!(
+                "Running Proton {} for {} with:\n{:#?}",
+                self.version,
+                self.program.to_string_lossy(),
+                launch.env(),
+            );
         }
 
-        // check two for runtimes
-        match self.version {
-            Version::Mainline(maj, _) => {
-                if maj >= 5 {
-                    let runtime = Runtime::from_proton(RunTimeVersion::Soldier, self)?;
-                    return runtime.execute()
-                }
-            },
-            Version::Experimental => {
-                let runtime = Runtime::from_proton(RunTimeVersion::Soldier, self)?;
-                return runtime.execute()
-            }
-            _ => {},
+        if !launch.unset_env().is_empty() {
+            println!("Unsetting:\n{:#?}", launch.unset_env());
         }
 
-        self.execute()
-    }
-
-    /// Executes Proton
-    fn execute(self) -> Result<ExitStatus, Error> {
-        use std::process::{Child, Command};
-
-        let envs: Vec<(String, String)> = self.gen_options();
-
-        println!(
-            "Running Proton {} for {} with:\n{:#?}",
-            self.version,
-            self.program.to_string_lossy(),
-            envs,
-        );
-
-        let mut child: Child = match Command::new(&self.path)
-            .arg("run")
-            .arg(&self.program)
-            .args(&self.args)
-            .env("STEAM_COMPAT_DATA_PATH", &self.compat)
-            .env("STEAM_COMPAT_CLIENT_INSTALL_PATH", &self.steam)
-            .envs(envs)
-            .spawn()
-        {
-            Ok(c) => c,
-            Err(e) => throw!(Kind::ProtonSpawn, "{}\nDebug:\n{:#?}", e, self),
-        };
-
-        let status: ExitStatus = match child.wait() {
-            Ok(e) => e,
-            Err(e) => throw!(Kind::ProtonWait, "'{}': {}", child.id(), e),
-        };
-
-        pass!(status)
+        launch.execute()
+    }
+
+    /// Applies the winetricks verbs to the prefix in use, skipping those it already has unless
+    /// `force`, and returns the verbs applied. Proton sets up the prefix first if it has not been
+    ///
+    /// # Errors
+    ///
+    /// Will fail on:
+    /// * Proton not existing
+    /// * Creating a Proton compat env directory fails
+    /// * Setting up the prefix fails
+    /// * Running winetricks or applying a verb fails
+    pub fn winetricks(mut self, force: bool) -> Result<Vec<String>, Error> {
+        let runtime: Option<Runtime> = self.prepare_proton()?;
+        self.create_p_dir()?;
+        self.record_version()?;
+
+        self.apply_winetricks(runtime.as_ref(), force)
+    }
+
+    /// Imports the registry files into the prefix in use with `regedit`, skipping those it already
+    /// has unless `force`, and returns the files imported. Proton sets up the prefix first if it has not been
+    ///
+    /// # Errors
+    ///
+    /// Will fail on:
+    /// * Proton not existing
+    /// * Creating a Proton compat env directory fails
+    /// * Setting up the prefix fails
+    /// * Reading or importing a registry file fails
+    pub fn import_registry(mut self, force: bool) -> Result<Vec<PathBuf>, Error> {
+        let runtime: Option<Runtime> = self.prepare_proton()?;
+        self.create_p_dir()?;
+        self.record_version()?;
+
+        self.apply_registry(runtime.as_ref(), force)
+    }
+
+    /// Changes `compat` path to the prefix in use, creates the directory if doesn't already exist,
+    /// then runs Proton with a path converting verb and returns the path it prints
+    ///
+    /// # Errors
+    ///
+    /// Will fail on:
+    /// * Creating a Proton compat env directory fails
+    /// * Reading Proton's `toolmanifest.vdf` fails
+    /// * The runtime is unknown or not installed
+    /// * Executing Proton fails, or it exits unsuccessfully
+    pub fn convert_path(mut self) -> Result<String, Error> {
+        let runtime: Option<Runtime> = self.prepare()?;
+        self.create_p_dir()?;
+        self.record_version()?;
+
+        Launch::from_proton(&self, runtime.as_ref()).output()
     }
 }
@@ -9,7 +9,7 @@ Run any Windows program through [Valve's Proton](https://github.com/ValveSoftwar
 
 ## Usage:
 
-Defaults to the latest version of Proton.
+Defaults to the latest version of Proton, or `default_version` from the config.
 ```
 proton-call -r foo.exe
 ```
@@ -26,37 +26,103 @@ Uses specified version of Proton, any extra arguments will be passed to the exec
 proton-call -p 5.13 -r foo.exe
 ```
 
+Versions can be given the same way their directories are named.
+```
+proton-call -p GE-Proton9-20 -r foo.exe
+```
+
 Uses custom version of Proton, give the past to directory, not the Proton executable itself.
 ```
 proton-call -c '/path/to/Proton version' -r foo.exe
+```
+
+Runs the game `foo` from the `[games.foo]` table of the config, any other options override the profile.
+```
+proton-call -g foo
+```
+
+Adds a custom build of Proton to the index, which can then be used by name with `-p`.
+```
+proton-call -a GE-Proton9-20 '/path/to/GE-Proton9-20'
+proton-call -p GE-Proton9-20 -r foo.exe
+```
+
+Lists installed Proton as JSON, or as tab separated values with `tsv`, sorted from newest to oldest.
+```
+proton-call --index --format json
+```
+
+Prints how Proton would be run, as a shell command or as JSON, without running anything.
+```
+proton-call --dry-run -r foo.exe
+proton-call --dry-run --format json -g foo
+```
+
+Indexes Proton in memory, without reading or writing the index cache.
+```
+proton-call --no-cache -r foo.exe
+```
+
+Runs a Wine tool in the prefix and with the Proton the game `foo` runs with.
+```
+proton-call -g foo --tool winecfg
 ```
  */
 
 extern crate jargon_args;
 extern crate lliw;
+extern crate serde_json;
+
+use jargon_args::Jargon;
+use lliw::Fg::LightYellow as Yellow;
+use lliw::Reset;
+use serde::Serialize;
 
+use proton_call::desktop::{self, DesktopEntry};
 use proton_call::error::{Error, Kind};
-use proton_call::{pass, throw, Config, Index, Proton, RunTimeVersion, RuntimeOption, Version};
-use std::path::PathBuf;
+use proton_call::icon::Icon;
+use proton_call::prefix::{self, Prefix, PrefixFilter, PrefixMeta};
+use proton_call::shortcuts::{self, Shortcut, Shortcuts};
+use proton_call::{
+    pass, throw, Config, DllOverride, Index, IndexEntry, IndexListing, Launch, Profile, Proton,
+    RunTimeVersion, RuntimeOption, RuntimeSetting, Tool, Verb, Version,
+};
+use std::io::{IsTerminal, Write};
+use std::path::{Path, PathBuf};
 use std::process::exit;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 /// Type to handle and parse command line arguments with `Jargon`
 #[derive(Debug)]
+#[allow(clippy::struct_field_names)]
 struct Args {
     program: PathBuf,
-    version: Version,
+    proton: Option<String>,
     custom: Option<PathBuf>,
-    options: Vec<RuntimeOption>,
+    options: Vec<RuntimeSetting>,
     args: Vec<String>,
     runtime_version: Option<RunTimeVersion>,
+    env: Vec<(String, String)>,
+    unset_env: Vec<String>,
+    dir: Option<PathBuf>,
+    prefix: Option<String>,
+    tool: Option<Tool>,
+    verb: Option<Verb>,
+    winetricks: Vec<String>,
+    registry: Vec<PathBuf>,
+    dlls: Vec<DllOverride>,
 }
 
 /// Main function which purely handles errors
 fn main() {
     let args: Vec<String> = std::env::args().collect();
-    let program: String = args[0].split('/').last().unwrap_or(&args[0]).to_string();
+    let program: String = args[0]
+        .split('/')
+        .next_back()
+        .unwrap_or(&args[0])
+        .to_string();
     if let Err(e) = proton_caller(args) {
-        eprintln!("{}: {}", program, e);
+        eprintln!("{program}: {e}");
         let code = e.kind() as i32;
         exit(code);
     }
@@ -64,87 +130,939 @@ fn main() {
 
 /// Effective main function which parses arguments
 fn proton_caller(args: Vec<String>) -> Result<(), Error> {
-    use jargon_args::Jargon;
-
     // args.insert(args.len(), "--index".to_string());
 
+    match args.get(1).map(String::as_str) {
+        Some("prefix") => return prefix_command(Jargon::from_vec(args[1..].to_vec())),
+        Some("winetricks") => return winetricks_command(Jargon::from_vec(args[1..].to_vec())),
+        Some("reg") => return reg_command(Jargon::from_vec(args[1..].to_vec())),
+        Some("desktop") => return desktop_command(Jargon::from_vec(args[1..].to_vec())),
+        Some("steam-shortcut") => {
+            return steam_shortcut_command(Jargon::from_vec(args[1..].to_vec()))
+        }
+        _ => {}
+    }
+
     let mut parser: Jargon = Jargon::from_vec(args);
+    let no_cache: bool = parser.contains("--no-cache");
 
     if parser.contains(["-h", "--help"]) {
         help();
     } else if parser.contains(["-v", "--version"]) {
         version();
+    } else if parser.contains("--list-options") {
+        list_options();
     } else if parser.contains(["-i", "--index"]) {
-        let config: Config = Config::open()?;
-        let common_index = Index::new(&config.common())?;
-        println!("{}", common_index);
+        let config: Config = open_config(no_cache)?;
+        let index = Index::new(&config)?;
+        let format: Option<String> = parser.option_arg("--format");
+
+        match format.as_deref() {
+            None | Some("text") => println!("{index}"),
+            Some("json") => print_json(&index.listing())?,
+            Some("tsv") => print_tsv(&index.listing()),
+            Some(format) => throw!(
+                Kind::ArgumentInvalid,
+                "--format '{}', expected json, tsv or text",
+                format
+            ),
+        }
     } else if parser.contains(["-a", "--add"]) {
-        todo!("command")
+        let config: Config = open_config(no_cache)?;
+        let mut index: Index = Index::new(&config)?;
+
+        match parser.finish().as_slice() {
+            [name, path] => {
+                let path: PathBuf = index.add(name, Path::new(path))?;
+                println!("Added Proton {}: {}", name, path.display());
+            }
+            _ => throw!(Kind::ArgumentMissing, "usage: --add NAME PATH"),
+        }
+    } else if parser.contains("--remove") {
+        let config: Config = open_config(no_cache)?;
+        let mut index: Index = Index::new(&config)?;
+
+        match parser.finish().as_slice() {
+            [name] => {
+                let path: PathBuf = index.remove(name)?;
+                println!("Removed Proton {}: {}", name, path.display());
+            }
+            _ => throw!(Kind::ArgumentMissing, "usage: --remove NAME"),
+        }
     } else {
-        let config: Config = Config::open()?;
-        let mut args = Args {
-            program: parser.result_arg(["-r", "--run"])?,
-            version: parser.option_arg(["-p", "--proton"]).unwrap_or_default(),
-            custom: parser.option_arg(["-c", "--custom"]),
-            runtime_version: parser.option_arg::<RunTimeVersion, [&str; 2]>(["-R", "--runtime"]),
-            options: Vec::new(),
-            args: Vec::new(),
-        };
+        run(parser, &open_config(no_cache)?)?;
+    }
 
-        let (options, argv) = if parser.contains(["-o", "--options"]) {
-            let mut opts: Vec<RuntimeOption> = Vec::new();
+    Ok(())
+}
 
-            if parser.contains(["-l", "--log"]) {
-                opts.insert(opts.len(), RuntimeOption::log)
+/// Runs the `prefix` subcommand, managing the prefixes in the data directory
+fn prefix_command(mut parser: Jargon) -> Result<(), Error> {
+    let config: Config = Config::open()?;
+    let data: PathBuf = config.data();
+    let force: bool = parser.contains(["-f", "--force"]);
+
+    let mut include: Vec<PathBuf> = Vec::new();
+    while let Some(path) = parser.option_arg::<PathBuf, _>("--include") {
+        include.insert(include.len(), path);
+    }
+
+    let mut exclude: Vec<PathBuf> = Vec::new();
+    while let Some(path) = parser.option_arg::<PathBuf, _>("--exclude") {
+        exclude.insert(exclude.len(), path);
+    }
+
+    let filter: PrefixFilter = PrefixFilter::new(include, exclude);
+
+    match parser.finish().as_slice() {
+        [cmd] if cmd == "list" => prefix_list(&config, &data)?,
+        [cmd, name] if cmd == "info" => prefix_info(&config, &data, name)?,
+        [cmd, from, to] if cmd == "clone" => {
+            let prefix: Prefix = Prefix::open(&data, from)?.clone_to(to)?;
+            println!("Cloned prefix {} to {}", from, prefix.path().display());
+        }
+        [cmd, name] if cmd == "rm" => {
+            let prefix: Prefix = Prefix::open(&data, name)?;
+
+            if !force
+                && !confirm(&format!(
+                    "Remove prefix {} ({})?",
+                    prefix.path().display(),
+                    format_size(prefix.size())
+                ))
+            {
+                println!("Prefix {name} was not removed, give --force to remove it without asking");
+                return pass!();
             }
 
-            let finish = parser.finish();
-            let mut arv = Vec::new();
+            prefix.remove()?;
+            println!("Removed prefix {name}");
+        }
+        [cmd, name, rest @ ..] if cmd == "backup" && rest.len() <= 1 => {
+            let prefix: Prefix = Prefix::open(&data, name)?;
+            let file: PathBuf = rest
+                .first()
+                .map_or_else(|| PathBuf::from(format!("{name}.tar.zst")), PathBuf::from);
 
-            for arg in finish {
-                if let Ok(opt) = arg.parse::<RuntimeOption>() {
-                    opts.insert(opts.len(), opt)
-                } else {
-                    arv.insert(arv.len(), arg)
+            if file.exists() && !force {
+                throw!(
+                    Kind::PrefixArchive,
+                    "'{}' exists, give --force to replace it",
+                    file.display()
+                );
+            }
+
+            let count: usize = prefix.backup(&file, &filter)?;
+            println!(
+                "Backed up prefix {name} to {} ({count} entries, {})",
+                file.display(),
+                format_size(file.metadata().map_or(0, |m| m.len()))
+            );
+        }
+        [cmd, file, rest @ ..] if cmd == "restore" && rest.len() <= 1 => {
+            let name: Option<&str> = rest.first().map(String::as_str);
+            let prefix: Prefix = Prefix::restore(&data, Path::new(file), name, &filter, force)?;
+            println!("Restored prefix {} from {file}", prefix.name());
+        }
+        _ => throw!(
+            Kind::ArgumentMissing,
+            "usage: prefix list | info NAME | clone FROM TO | rm [--force] NAME\n       \
+            prefix backup [--force] NAME [FILE] | restore [--force] FILE [NAME]"
+        ),
+    }
+
+    pass!()
+}
+
+/// Parses the runtime options given to `-o` and `-l`, and the arguments left for the program
+fn parse_options(mut parser: Jargon) -> Result<(Vec<RuntimeSetting>, Vec<String>), Error> {
+    let (options, extra) = if parser.contains(["-o", "--options"]) {
+        let mut opts: Vec<RuntimeSetting> = Vec::new();
+
+        if parser.contains(["-l", "--log"]) {
+            opts.insert(opts.len(), RuntimeSetting::new(RuntimeOption::log));
+        }
+
+        let finish = parser.finish();
+        let mut arv = Vec::new();
+
+        for arg in finish {
+            match arg.parse::<RuntimeSetting>() {
+                Ok(opt) => opts.insert(opts.len(), opt),
+                // an option missing its value is a mistake, not an argument for the program
+                Err(e) if arg.parse::<RuntimeOption>().is_ok() => return Err(e),
+                Err(_) => arv.insert(arv.len(), arg),
+            }
+        }
+
+        (opts, arv)
+    } else {
+        let mut opts: Vec<RuntimeSetting> = Vec::new();
+
+        if parser.contains(["-l", "--log"]) {
+            opts.insert(opts.len(), RuntimeSetting::new(RuntimeOption::log));
+        }
+
+        (opts, parser.finish())
+    };
+
+    pass!((options, extra))
+}
+
+/// Checks the Proton version last run in the prefix against the one about to run. A downgrade is
+/// refused unless `allow_downgrade`, an upgrade copies the prefix first if `snapshot` or the user
+/// agrees. The switch is recorded in the prefix's metadata
+fn guard_prefix(
+    config: &Config,
+    proton: &Proton,
+    allow_downgrade: bool,
+    snapshot: bool,
+) -> Result<(), Error> {
+    let name: String = proton.prefix_name();
+
+    // a prefix which does not exist yet has nothing to guard
+    let Ok(prefix) = Prefix::open(&config.data(), &name) else {
+        return pass!();
+    };
+
+    let new: Version = proton.version();
+
+    let Some(old) = prefix.recorded_version() else {
+        return pass!();
+    };
+
+    // switching to or from a rolling release or custom build is neither a known upgrade nor downgrade
+    if !old.is_comparable(new) || old.is_same_release(new) {
+        return pass!();
+    }
+
+    let mut snapshot_name: Option<String> = None;
+
+    if new < old {
+        if !allow_downgrade {
+            throw!(
+                Kind::PrefixDowngrade,
+                "'{}' was last run with Proton {}, not {}, give --allow-downgrade to run it anyway",
+                name,
+                old,
+                new
+            );
+        }
+
+        eprintln!("{Yellow}warning:{Reset} downgrading prefix {name} from Proton {old} to {new}");
+    } else if snapshot
+        || (std::io::stdin().is_terminal()
+            && confirm(&format!(
+                "Prefix {name} will be upgraded from Proton {old} to {new}, snapshot it first?"
+            )))
+    {
+        let secs: u64 = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        let copy: Prefix = prefix.clone_to(&format!("{name}@{old}-{secs}"))?;
+
+        println!("Saved a snapshot of prefix {name} as {}", copy.name());
+        snapshot_name = Some(copy.name());
+    } else {
+        println!(
+            "Upgrading prefix {name} from Proton {old} to {new}, give --snapshot to copy it first"
+        );
+    }
+
+    let mut meta: PrefixMeta = PrefixMeta::open(&prefix)?;
+    meta.record(new, snapshot_name);
+    meta.save(&prefix)
+}
+
+/// Prints every prefix with its size, last Proton, creation time and games
+fn prefix_list(config: &Config, data: &Path) -> Result<(), Error> {
+    println!(
+        "{:<32} {:>10} {:<24} {:<20} GAMES",
+        "PREFIX", "SIZE", "PROTON", "CREATED"
+    );
+
+    for prefix in Prefix::list(data)? {
+        println!(
+            "{:<32} {:>10} {:<24} {:<20} {}",
+            prefix.name(),
+            format_size(prefix.size()),
+            prefix.proton().unwrap_or_else(|| "-".to_string()),
+            prefix
+                .created()
+                .map_or_else(|| "-".to_string(), format_time),
+            config.games_in_prefix(&prefix.name()).join(", "),
+        );
+    }
+
+    pass!()
+}
+
+/// Prints the details of the prefix `name`
+fn prefix_info(config: &Config, data: &Path, name: &str) -> Result<(), Error> {
+    let prefix: Prefix = Prefix::open(data, name)?;
+    let none = || "-".to_string();
+
+    println!("Prefix: {}", prefix.name());
+    println!("Path: {}", prefix.path().display());
+    println!("Size: {}", format_size(prefix.size()));
+    println!("Proton: {}", prefix.proton().unwrap_or_else(none));
+    println!(
+        "Prefix version: {}",
+        prefix.prefix_version().unwrap_or_else(none)
+    );
+    println!(
+        "Created: {}",
+        prefix.created().map_or_else(none, format_time)
+    );
+    println!("Games: {}", config.games_in_prefix(name).join(", "));
+
+    let meta: PrefixMeta = PrefixMeta::open(&prefix)?;
+    println!("Winetricks: {}", meta.winetricks().join(", "));
+
+    for (file, hash) in meta.registry() {
+        println!("Registry: {file} ({hash})");
+    }
+
+    for switch in meta.switches() {
+        let time: SystemTime = UNIX_EPOCH + Duration::from_secs(switch.time);
+
+        match switch.snapshot {
+            Some(snapshot) => println!(
+                "Switched: {} to {} on {}, snapshot {}",
+                switch.from,
+                switch.to,
+                format_time(time),
+                snapshot
+            ),
+            None => println!(
+                "Switched: {} to {} on {}",
+                switch.from,
+                switch.to,
+                format_time(time)
+            ),
+        }
+    }
+
+    pass!()
+}
+
+/// Runs the `winetricks` subcommand, applying verbs to the prefix a game or program runs in
+fn winetricks_command(mut parser: Jargon) -> Result<(), Error> {
+    let config: Config = open_config(parser.contains("--no-cache"))?;
+    let force: bool = parser.contains(["-f", "--force"]);
+    let mut args: Args = target_args(&mut parser, &config)?;
+
+    // verbs given replace the game's
+    let verbs: Vec<String> = parser.finish();
+    if !verbs.is_empty() {
+        args.winetricks = verbs;
+    }
+
+    if args.winetricks.is_empty() {
+        throw!(
+            Kind::ArgumentMissing,
+            "usage: winetricks [-g GAME] [-r EXE] [-p VERSION] [--prefix NAME] [--force] VERB..."
+        );
+    }
+
+    let proton: Proton = target_proton(&config, args)?;
+    let name: String = proton.prefix_name();
+    let applied: Vec<String> = proton.winetricks(force)?;
+
+    if applied.is_empty() {
+        println!("Prefix {name} already has every verb, give --force to apply them again");
+    } else {
+        println!("Applied {} to prefix {name}", applied.join(", "));
+    }
+
+    pass!()
+}
+
+/// Runs the `reg` subcommand, importing registry files into the prefix a game or program runs in
+fn reg_command(mut parser: Jargon) -> Result<(), Error> {
+    let config: Config = open_config(parser.contains("--no-cache"))?;
+    let force: bool = parser.contains(["-f", "--force"]);
+    let mut args: Args = target_args(&mut parser, &config)?;
+
+    let rest: Vec<String> = parser.finish();
+    let files: Vec<PathBuf> = match rest.split_first() {
+        Some((cmd, files)) if cmd == "import" => files.iter().map(PathBuf::from).collect(),
+        _ => Vec::new(),
+    };
+
+    // files given replace the game's
+    if !files.is_empty() {
+        args.registry = files;
+    }
+
+    if rest.first().map(String::as_str) != Some("import") || args.registry.is_empty() {
+        throw!(
+            Kind::ArgumentMissing,
+            "usage: reg import [-g GAME] [-r EXE] [-p VERSION] [--prefix NAME] [--force] [FILE]..."
+        );
+    }
+
+    let proton: Proton = target_proton(&config, args)?;
+    let name: String = proton.prefix_name();
+    let imported: Vec<PathBuf> = proton.import_registry(force)?;
+
+    if imported.is_empty() {
+        println!("Prefix {name} already has every file, give --force to import them again");
+    }
+
+    for file in imported {
+        println!("Imported {} into prefix {name}", file.display());
+    }
+
+    pass!()
+}
+
+/// Adds a game to Steam's library as a non-Steam game run through `proton-call`, or turns the
+/// non-Steam games which run a `.exe` into games of the config
+fn steam_shortcut_command(mut parser: Jargon) -> Result<(), Error> {
+    let config: Config = open_config(parser.contains("--no-cache"))?;
+    let user: Option<String> = parser.option_arg("--user");
+    let rest: Vec<String> = parser.finish();
+
+    let path: PathBuf = Shortcuts::find(&config.steam(), user.as_deref())?;
+    let mut shortcuts: Shortcuts = Shortcuts::open(&path)?;
+
+    match rest.as_slice() {
+        [cmd, name] if cmd == "add" => {
+            let profile: Profile = config.game(name)?;
+
+            let exe: PathBuf = match std::env::current_exe() {
+                Ok(exe) => exe,
+                Err(e) => throw!(Kind::Shortcuts, "can not find proton-call: {}", e),
+            };
+
+            let dir: PathBuf = match profile.dir() {
+                Some(dir) => dir,
+                None => profile
+                    .exe()
+                    .parent()
+                    .map(Path::to_path_buf)
+                    .unwrap_or_default(),
+            };
+
+            // Steam shows the game with the same name as its launchers
+            let shortcut: Shortcut = Shortcut::new(
+                &profile.name().unwrap_or_else(|| name.clone()),
+                &format!("\"{}\"", exe.display()),
+                &format!("\"{}\"", dir.display()),
+                &format!("--game {}", steam_quote(name)),
+            )
+            .with_icon(install_icon(name, &profile).as_deref());
+
+            let verb: &str = if shortcuts.set(&shortcut) {
+                "Updated"
+            } else {
+                "Added"
+            };
+            shortcuts.save()?;
+
+            println!(
+                "{verb} {name} in {} with App ID {}",
+                path.display(),
+                shortcut.appid()
+            );
+            eprintln!("{Yellow}warning:{Reset} Steam only reads shortcuts when it starts, and overwrites them if it is running");
+        }
+        [cmd] if cmd == "import" => {
+            let mut games: Vec<String> = config.games();
+
+            for shortcut in shortcuts.list() {
+                let exe: PathBuf = PathBuf::from(shortcuts::unquote(&shortcut.exe()));
+
+                let is_exe: bool = exe
+                    .extension()
+                    .is_some_and(|ext| ext.eq_ignore_ascii_case("exe"));
+
+                if !is_exe {
+                    continue;
+                }
+
+                let name: String = game_name(&shortcut);
+
+                if games.contains(&name) {
+                    println!(
+                        "Skipped {}, the game {name} already exists",
+                        shortcut.name()
+                    );
+                    continue;
                 }
+
+                // Steam runs what is around `%command%` as a command line, only what follows it goes to the program
+                let options: String = shortcut.launch_options();
+                let options: &str = match options.split_once("%command%") {
+                    Some((_, after)) => after,
+                    None => &options,
+                };
+                let args: Vec<String> = options.split_whitespace().map(String::from).collect();
+
+                let dir: String = shortcuts::unquote(&shortcut.start_dir());
+                let dir: Option<&Path> = if dir.is_empty() {
+                    None
+                } else {
+                    Some(Path::new(&dir))
+                };
+
+                // keeping the shortcut's name, so `add` updates the same shortcut
+                Config::append_game(&name, Some(&shortcut.name()), &exe, dir, &args)?;
+                games.insert(games.len(), name.clone());
+
+                println!("Imported {} as the game {name}", shortcut.name());
             }
+        }
+        _ => throw!(
+            Kind::ArgumentMissing,
+            "usage: steam-shortcut [--user ID] add GAME | steam-shortcut [--user ID] import"
+        ),
+    }
 
-            (opts, arv)
-        } else {
-            let mut opts: Vec<RuntimeOption> = Vec::new();
+    pass!()
+}
 
-            if parser.contains(["-l", "--log"]) {
-                opts.insert(opts.len(), RuntimeOption::log)
+/// Installs or removes the freedesktop launcher of a game
+fn desktop_command(mut parser: Jargon) -> Result<(), Error> {
+    let config: Config = open_config(parser.contains("--no-cache"))?;
+    let rest: Vec<String> = parser.finish();
+
+    match rest.as_slice() {
+        [cmd, name] if cmd == "install" => {
+            let profile: Profile = config.game(name)?;
+
+            let exe: PathBuf = match std::env::current_exe() {
+                Ok(exe) => exe,
+                Err(e) => throw!(Kind::Desktop, "can not find proton-call: {}", e),
+            };
+
+            let icon: Option<String> = install_icon(name, &profile).map(|_| desktop::id(name));
+            let path: PathBuf = DesktopEntry::new(name, &profile, &exe)
+                .with_icon(icon)
+                .install()?;
+            println!("Installed the launcher of {name} to {}", path.display());
+        }
+        [cmd, name] if cmd == "remove" => {
+            let path: PathBuf = DesktopEntry::remove(name)?;
+            println!("Removed the launcher of {name} from {}", path.display());
+
+            // the icon is kept while the game still has a Steam shortcut which shows it
+            if !steam_has_icon(&config, name) {
+                Icon::remove(&desktop::id(name))?;
             }
+        }
+        _ => throw!(
+            Kind::ArgumentMissing,
+            "usage: desktop install GAME | desktop remove GAME"
+        ),
+    }
 
-            (opts, parser.finish())
-        };
+    pass!()
+}
 
-        args.options = options;
-        args.args = argv;
+/// Extracts the icon of the game `name` into the icon theme, named after its launcher, and returns
+/// its largest image. Games without an icon only get a warning
+fn install_icon(name: &str, profile: &Profile) -> Option<PathBuf> {
+    let installed: Result<Vec<PathBuf>, Error> =
+        Icon::extract(&profile.exe()).and_then(|icon| icon.install(&desktop::id(name)));
 
-        let proton = if args.custom.is_some() {
-            custom_mode(&config, args)?
-        } else {
-            normal_mode(&config, args)?
-        };
+    match installed {
+        Ok(paths) => paths.into_iter().next(),
+        Err(e) => {
+            eprintln!("{Yellow}warning:{Reset} {e}, {name} is shown without its icon");
+            None
+        }
+    }
+}
+
+/// Returns true if a Steam shortcut of the game `name` shows its icon
+fn steam_has_icon(config: &Config, name: &str) -> bool {
+    let icons: Vec<String> = Shortcuts::find(&config.steam(), None)
+        .and_then(|path| Shortcuts::open(&path))
+        .map(|shortcuts| shortcuts.list().iter().map(Shortcut::icon).collect())
+        .unwrap_or_default();
+
+    icons
+        .iter()
+        .any(|icon| Path::new(icon).file_stem() == Some(desktop::id(name).as_ref()))
+}
+
+/// Names a game after a shortcut: lowercase, with anything but letters and digits turned into `-`
+fn game_name(shortcut: &Shortcut) -> String {
+    let name: String = shortcut
+        .name()
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<&str>>()
+        .join("-");
+
+    if name.is_empty() {
+        format!("game-{}", shortcut.appid())
+    } else {
+        name
+    }
+}
+
+/// Quotes `arg` for Steam's launch options, which are split on spaces outside of double quotes
+fn steam_quote(arg: &str) -> String {
+    if !arg.is_empty() && !arg.contains([' ', '"']) {
+        return arg.to_string();
+    }
+
+    format!("\"{}\"", arg.replace('"', "\\\""))
+}
+
+/// Parses the game, program, Proton and prefix a subcommand works on, the same as a launch would
+fn target_args(parser: &mut Jargon, config: &Config) -> Result<Args, Error> {
+    let game: Option<String> = parser.option_arg(["-g", "--game"]);
+    let profile: Option<Profile> = match &game {
+        Some(name) => Some(config.game(name)?),
+        None => None,
+    };
+
+    let program: Option<PathBuf> = parser.option_arg(["-r", "--run"]);
+
+    let mut args = Args {
+        program: match (&program, &profile) {
+            (Some(program), _) => program.clone(),
+            (None, Some(profile)) => profile.exe(),
+            (None, None) => PathBuf::new(),
+        },
+        proton: parser.option_arg(["-p", "--proton"]),
+        custom: parser.option_arg(["-c", "--custom"]),
+        runtime_version: parser.option_arg::<RunTimeVersion, [&str; 2]>(["-R", "--runtime"]),
+        options: Vec::new(),
+        args: Vec::new(),
+        env: Vec::new(),
+        unset_env: Vec::new(),
+        dir: None,
+        prefix: parser.option_arg("--prefix"),
+        tool: None,
+        verb: None,
+        winetricks: Vec::new(),
+        registry: Vec::new(),
+        dlls: Vec::new(),
+    };
+
+    if let (Some(name), Some(profile)) = (&game, &profile) {
+        apply_profile(&mut args, name, profile);
+    }
+
+    if args.prefix.is_none() && program.is_some() && config.exe_prefixes() {
+        args.prefix = Some(prefix::exe_name(&args.program));
+    }
+
+    let mut env: Vec<(String, String)> = config.env();
+    env.extend(args.env);
+    args.env = env;
+
+    pass!(args)
+}
+
+/// Finds the Proton of a subcommand's target, and guards its prefix against a downgrade
+fn target_proton(config: &Config, args: Args) -> Result<Proton, Error> {
+    let proton: Proton = if args.custom.is_some() {
+        custom_mode(config, args)?
+    } else {
+        normal_mode(config, args)?
+    };
 
-        let exit = proton.run()?;
+    guard_prefix(config, &proton, false, false)?;
 
-        if !exit.success() {
-            if let Some(code) = exit.code() {
-                throw!(Kind::ProtonExit, "code: {}", code);
+    pass!(proton)
+}
+
+/// Asks `question` on the terminal, true if it is answered yes
+fn confirm(question: &str) -> bool {
+    print!("{question} [y/N] ");
+    let _ = std::io::stdout().flush();
+
+    let mut answer: String = String::new();
+    if std::io::stdin().read_line(&mut answer).is_err() {
+        return false;
+    }
+
+    matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+}
+
+/// Formats a size in bytes with a binary unit, such as `1.5 GiB`
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+
+    let mut unit: usize = 0;
+    let mut scale: u64 = 1;
+
+    while unit + 1 < UNITS.len() && bytes >= scale * 1024 {
+        scale *= 1024;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        return format!("{bytes} B");
+    }
+
+    let tenths: u64 = bytes * 10 / scale;
+    format!("{}.{} {}", tenths / 10, tenths % 10, UNITS[unit])
+}
+
+/// Formats a time as a UTC date and time, such as `2021-12-24 18:30 UTC`
+fn format_time(time: SystemTime) -> String {
+    let secs: u64 = time.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+    let (days, rem): (u64, u64) = (secs / 86_400, secs % 86_400);
+
+    // days since the epoch to a civil date, from Howard Hinnant's `civil_from_days`
+    let z: u64 = days + 719_468;
+    let era: u64 = z / 146_097;
+    let doe: u64 = z % 146_097;
+    let yoe: u64 = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy: u64 = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp: u64 = (5 * doy + 2) / 153;
+    let day: u64 = doy - (153 * mp + 2) / 5 + 1;
+    let month: u64 = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year: u64 = yoe + era * 400 + u64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02} UTC",
+        rem / 3600,
+        rem % 3600 / 60
+    )
+}
+
+/// Runs a program in Proton, from the command line and a game profile
+fn run(mut parser: Jargon, config: &Config) -> Result<(), Error> {
+    let game: Option<String> = parser.option_arg(["-g", "--game"]);
+
+    let profile: Option<Profile> = match &game {
+        Some(name) => Some(config.game(name)?),
+        None => None,
+    };
+
+    let program: Option<PathBuf> = parser.option_arg(["-r", "--run"]);
+
+    let tool: Option<Tool> = match parser.option_arg::<String, _>(["-t", "--tool"]) {
+        Some(tool) => Some(tool.parse()?),
+        None => None,
+    };
+
+    let verb: Option<Verb> = match parser.option_arg::<String, _>("--verb") {
+        Some(verb) => Some(verb.parse()?),
+        None => None,
+    };
+
+    if let (Some(tool), Some(verb)) = (tool, verb) {
+        if verb.converts_path() {
+            throw!(
+                Kind::ArgumentInvalid,
+                "--tool {} with --verb {}",
+                tool,
+                verb
+            );
+        }
+    }
+
+    // a program given with a tool still picks the prefix and directory the tool runs in
+    let exe_prefix: bool = program.is_some() || tool.is_none();
+
+    let mut args = Args {
+        program: match (program, &profile, tool) {
+            (Some(program), _, _) => program,
+            (None, Some(profile), _) => profile.exe(),
+            (None, None, Some(tool)) => PathBuf::from(tool.exe()),
+            (None, None, None) => throw!(Kind::ArgumentMissing, "-r, --run"),
+        },
+        proton: parser.option_arg(["-p", "--proton"]),
+        custom: parser.option_arg(["-c", "--custom"]),
+        runtime_version: parser.option_arg::<RunTimeVersion, [&str; 2]>(["-R", "--runtime"]),
+        options: Vec::new(),
+        args: Vec::new(),
+        env: Vec::new(),
+        unset_env: Vec::new(),
+        dir: None,
+        prefix: parser.option_arg("--prefix"),
+        tool,
+        verb,
+        winetricks: Vec::new(),
+        registry: Vec::new(),
+        dlls: Vec::new(),
+    };
+
+    while let Some(var) = parser.option_arg::<String, _>(["-e", "--env"]) {
+        match var.split_once('=') {
+            Some((key, value)) if !key.is_empty() => {
+                args.env
+                    .insert(args.env.len(), (key.to_string(), value.to_string()));
             }
-            throw!(Kind::ProtonExit, "an error");
+            _ => throw!(Kind::ArgumentInvalid, "-e '{}', expected KEY=VALUE", var),
         }
     }
 
-    Ok(())
+    while let Some(key) = parser.option_arg::<String, _>("--unset-env") {
+        args.unset_env.insert(args.unset_env.len(), key);
+    }
+
+    while let Some(dll) = parser.option_arg::<String, _>("--dll") {
+        args.dlls.insert(args.dlls.len(), dll.parse()?);
+    }
+
+    let dry_run: bool = parser.contains("--dry-run");
+    let allow_downgrade: bool = parser.contains("--allow-downgrade");
+    let snapshot: bool = parser.contains("--snapshot");
+    let format: Option<String> = parser.option_arg("--format");
+
+    let (options, extra) = parse_options(parser)?;
+
+    args.options = options;
+    args.args = extra;
+
+    if let (Some(name), Some(profile)) = (&game, &profile) {
+        apply_profile(&mut args, name, profile);
+    }
+
+    if args.prefix.is_none() && exe_prefix && config.exe_prefixes() {
+        args.prefix = Some(prefix::exe_name(&args.program));
+    }
+
+    // the config's environment comes first, so a profile or the command line override it
+    let mut env: Vec<(String, String)> = config.env();
+    env.extend(args.env);
+    args.env = env;
+
+    let proton = if args.custom.is_some() {
+        custom_mode(config, args)?
+    } else {
+        normal_mode(config, args)?
+    };
+
+    if dry_run {
+        return print_launch(proton, format.as_deref());
+    }
+
+    guard_prefix(config, &proton, allow_downgrade, snapshot)?;
+
+    // only the converted path is printed, for scripts to read
+    if verb.is_some_and(Verb::converts_path) {
+        println!("{}", proton.convert_path()?);
+        return pass!();
+    }
+
+    let exit = proton.run()?;
+
+    if !exit.success() {
+        if let Some(code) = exit.code() {
+            throw!(Kind::ProtonExit, "code: {}", code);
+        }
+        throw!(Kind::ProtonExit, "an error");
+    }
+
+    pass!()
+}
+
+/// Prints how `proton` would be launched, as a shell command or as JSON, without running anything
+fn print_launch(proton: Proton, format: Option<&str>) -> Result<(), Error> {
+    let launch: Launch = proton.launch()?;
+
+    match format {
+        None | Some("shell") => println!("{launch}"),
+        Some("json") => print_json(&launch)?,
+        Some(format) => throw!(
+            Kind::ArgumentInvalid,
+            "--format '{}', expected json or shell",
+            format
+        ),
+    }
+
+    pass!()
+}
+
+/// Prints `value` as pretty JSON
+fn print_json<T: Serialize>(value: &T) -> Result<(), Error> {
+    match serde_json::to_string_pretty(value) {
+        Ok(json) => println!("{json}"),
+        Err(e) => throw!(Kind::Internal, "{}", e),
+    }
+
+    pass!()
+}
+
+/// Prints the listing of an index as tab separated values, with a header row
+fn print_tsv(listing: &[IndexListing]) {
+    let escape = |s: &str| {
+        s.replace('\\', "\\\\")
+            .replace('\t', "\\t")
+            .replace('\n', "\\n")
+    };
+
+    println!("version\tname\tpath\tlibrary\tflavour\truntime\tadded");
+
+    for row in listing {
+        println!(
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            escape(&row.version()),
+            escape(&row.name().unwrap_or_default()),
+            escape(&row.path().to_string_lossy()),
+            escape(&row.library().to_string_lossy()),
+            row.flavour(),
+            row.runtime().unwrap_or_default(),
+            row.added(),
+        );
+    }
+}
+
+/// Opens the config, the index cache is disabled with `--no-cache`
+fn open_config(no_cache: bool) -> Result<Config, Error> {
+    let mut config: Config = Config::open()?;
+
+    if no_cache {
+        config.disable_cache();
+    }
+
+    pass!(config)
+}
+
+/// Fills in values from the profile of the game `name` which were not given on the command line
+fn apply_profile(args: &mut Args, name: &str, profile: &Profile) {
+    if args.prefix.is_none() {
+        args.prefix = Some(profile.prefix().unwrap_or_else(|| name.to_string()));
+    }
+
+    if args.proton.is_none() {
+        args.proton = profile.proton();
+    }
+
+    if args.runtime_version.is_none() {
+        args.runtime_version = profile.runtime();
+    }
+
+    let mut options: Vec<RuntimeSetting> = profile.options();
+    for opt in &args.options {
+        options.retain(|o| o.option() != opt.option());
+        options.insert(options.len(), opt.clone());
+    }
+    args.options = options;
+
+    // the game's arguments are for the game, not a tool run in its prefix
+    if args.args.is_empty() && args.tool.is_none() {
+        args.args = profile.args();
+    }
+
+    let mut env: Vec<(String, String)> = profile.env();
+    env.append(&mut args.env);
+    args.env = env;
+    args.dir = profile.dir();
+    args.winetricks = profile.winetricks();
+    args.registry = profile.registry();
+
+    // the command line's overrides come last, so they win
+    let mut dlls: Vec<DllOverride> = profile.dlls();
+    dlls.append(&mut args.dlls);
+    args.dlls = dlls;
 }
 
-fn get_proton_path(index: &mut Index, version: Version) -> Result<PathBuf, Error> {
-    if let Some(path) = index.get(&version) {
-        return Ok(path);
+fn get_proton(index: &mut Index, version: Version) -> Result<IndexEntry, Error> {
+    if let Some(entry) = index.get_entry(&version) {
+        return Ok(entry.clone());
     }
 
     eprintln!(
@@ -154,23 +1072,64 @@ fn get_proton_path(index: &mut Index, version: Version) -> Result<PathBuf, Error
         version
     );
     index.index()?;
-    index.get(&version).ok_or_else(|| {
+    index.get_entry(&version).cloned().ok_or_else(|| {
         Error::new(
             Kind::ProtonMissing,
-            format!("Proton {} does not exist", version),
+            format!("Proton {version} does not exist"),
         )
     })
 }
 
+/// Finds the Proton added or indexed as `name`, or parses `name` as a version
+fn find_proton(index: &mut Index, name: &str) -> Result<(Version, IndexEntry), Error> {
+    if let Some(entry) = index.get_named(name) {
+        return pass!((Version::from_custom(&entry.path()), entry.clone()));
+    }
+
+    let version: Version = name.parse()?;
+    pass!((version, get_proton(index, version)?))
+}
+
+/// Finds the newest installed mainline Proton
+fn latest_proton(index: &mut Index) -> Result<(Version, IndexEntry), Error> {
+    if let Some(latest) = index.latest() {
+        return pass!(latest);
+    }
+
+    eprintln!(
+        "{}info:{} no Proton found, reindexing...",
+        lliw::Fg::Blue,
+        lliw::Reset,
+    );
+    index.index()?;
+    match index.latest() {
+        Some(latest) => pass!(latest),
+        None => throw!(Kind::ProtonMissing, "no Proton versions are installed"),
+    }
+}
+
 /// Runs caller in normal mode, running indexed Proton versions
 fn normal_mode(config: &Config, args: Args) -> Result<Proton, Error> {
-    let mut index: Index = Index::new(&config.common())?;
+    let mut index: Index = Index::new(config)?;
 
-    let proton_path: PathBuf = get_proton_path(&mut index, args.version)?;
+    let (version, entry): (Version, IndexEntry) = if let Some(name) = &args.proton {
+        find_proton(&mut index, name)?
+    } else if let Some(name) = config.default_version() {
+        match find_proton(&mut index, &name) {
+            Err(e) if e.kind() == Kind::ProtonMissing => throw!(
+                Kind::ProtonMissing,
+                "default_version '{}' from the config is not installed",
+                name
+            ),
+            result => result?,
+        }
+    } else {
+        latest_proton(&mut index)?
+    };
 
     let proton: Proton = Proton::new(
-        args.version,
-        proton_path,
+        version,
+        entry.path(),
         args.program,
         args.args,
         args.options,
@@ -178,7 +1137,17 @@ fn normal_mode(config: &Config, args: Args) -> Result<Proton, Error> {
         config.steam(),
         args.runtime_version,
         config.common(),
-    );
+    )
+    .with_env(args.env)
+    .with_unset_env(args.unset_env)
+    .with_dir(args.dir)
+    .with_prefix(args.prefix)
+    .with_tool(args.tool)
+    .with_verb(args.verb)
+    .with_winetricks(args.winetricks)
+    .with_registry(args.registry)
+    .with_dlls(args.dlls)
+    .with_libraries(Index::directories(config));
 
     pass!(proton)
 }
@@ -196,7 +1165,17 @@ fn custom_mode(config: &Config, args: Args) -> Result<Proton, Error> {
             config.steam(),
             args.runtime_version,
             config.common(),
-        );
+        )
+        .with_env(args.env)
+        .with_unset_env(args.unset_env)
+        .with_dir(args.dir)
+        .with_prefix(args.prefix)
+        .with_tool(args.tool)
+        .with_verb(args.verb)
+        .with_winetricks(args.winetricks)
+        .with_registry(args.registry)
+        .with_dlls(args.dlls)
+        .with_libraries(Index::directories(config));
 
         return pass!(proton);
     }
@@ -207,33 +1186,141 @@ fn custom_mode(config: &Config, args: Args) -> Result<Proton, Error> {
 #[doc(hidden)]
 static HELP: &str = "\
 Usage: proton-call [OPTIONS]... EXE [EXTRA]...
+       proton-call prefix list | info NAME | clone FROM TO | rm [--force] NAME
+       proton-call prefix backup NAME [FILE] | restore FILE [NAME]
+       proton-call winetricks [-g GAME] [-r EXE] [-p VERSION] [--prefix NAME] [--force] VERB...
+       proton-call reg import [-g GAME] [-r EXE] [-p VERSION] [--prefix NAME] [--force] [FILE]...
+       proton-call steam-shortcut [--user ID] add GAME | import
+       proton-call desktop install GAME | remove GAME
 
 Options:
+    -a, --add NAME PATH     Add the Proton in PATH to the index as NAME
     -c, --custom [PATH]     Path to a directory containing Proton to use
+        --allow-downgrade   Run a prefix with an older Proton than it was last run with
+        --dll NAME=ORDER    Override the load order of the DLL NAME, such as 'dinput8=n,b', or disable it
+                            with 'NAME=', can be repeated. Merged into WINEDLLOVERRIDES
+        --dry-run           Print the command, environment and directory Proton would be run with
+                            as a shell command, or as json with `--format json`
+    -e, --env KEY=VALUE     Set an environment variable for the program, can be repeated
+    -g, --game NAME         Run the game NAME from the config
     -h, --help              View this help message
     -i, --index             View an index of installed Proton versions
+        --format FORMAT     Print the index as json, tsv or text, or a dry run as json or shell
     -l, --log               Pass PROTON_LOG variable to Proton
-    -o, --options [OPTIONS] Pass options to Runtime
-    -p, --proton [VERSION]  Use Proton VERSION from `common`
+        --list-options      View every runtime option, its variable and description
+        --no-cache          Index Proton in memory, without reading or writing the index cache
+    -o, --options [OPTIONS] Pass options to Proton, as NAME or NAME=VALUE
+    -p, --proton [VERSION]  Use Proton VERSION from the index, or one added with --add
+        --prefix NAME       Run in the prefix NAME in the data directory, instead of the game's or the version's
     -r, --run EXE           Run EXE in proton
+        --remove NAME       Remove the Proton added as NAME from the index
     -R, --runtime [VERSION] Use runtime VERSION
+    -t, --tool TOOL         Run the Wine tool winecfg, regedit, wineboot, cmd or taskmgr in place of EXE,
+                            in the prefix and Proton EXE or the game would run with
+        --snapshot          Copy the prefix before upgrading it to a newer Proton, without asking
+        --verb VERB         Run Proton with VERB: run, waitforexitandrun, runinprefix, or getcompatpath and
+                            getnativepath to print EXE converted to a Windows or Unix path in the prefix.
+                            Defaults to waitforexitandrun in a runtime, run otherwise
+        --unset-env KEY     Remove an environment variable for the program, can be repeated
     -v, --version           View version information
 
+Prefix:
+    list                    List every prefix with its size, last Proton, creation time and games
+    info NAME               View the details of the prefix NAME
+    clone FROM TO           Copy the prefix FROM into a new prefix TO
+    rm [-f, --force] NAME   Remove the prefix NAME, asking first unless given --force
+    backup NAME [FILE]      Archive the prefix NAME into FILE, 'NAME.tar.zst' otherwise,
+                            replacing an existing FILE only with --force
+    restore FILE [NAME]     Restore a backup as the prefix NAME, or the name it was backed up with,
+                            replacing an existing prefix only with --force
+        --include PATH      Only back up or restore PATH in the prefix, can be repeated
+        --exclude PATH      Skip PATH in the prefix, such as 'drive_c/windows', can be repeated
+
+Winetricks:
+    Applies winetricks VERBs, or the game's `winetricks`, to the prefix and with the Wine of the Proton
+    a game or program runs with. Verbs a prefix already has are skipped unless given --force.
+    The verbs applied are kept in the prefix's 'proton-call.toml'.
+
+Registry:
+    import [FILE]...        Import registry FILEs, or the game's `registry`, into the prefix with regedit.
+                            Files already imported, by the hash of their contents, are skipped unless
+                            given --force.
+
+Steam Shortcut:
+    add GAME                Add the game GAME to Steam's library as a non-Steam game which runs
+                            `proton-call --game GAME`, updating it if it is already there, shown
+                            with the icon of the game's exe
+    import                  Add the non-Steam games which run a '.exe' to the config as games
+        --user ID           Use the shortcuts of the Steam user ID in Steam's 'userdata', needed when
+                            there is more than one. Steam should be closed, it overwrites them otherwise.
+
+Desktop:
+    install GAME            Write a launcher for the game GAME, which runs `proton-call --game GAME`,
+                            to '$XDG_DATA_HOME/applications' or '$HOME/.local/share/applications'.
+                            The icon of the game's exe is extracted into the hicolor icon theme
+    remove GAME             Remove the launcher of the game GAME, and its icon unless a Steam shortcut
+                            shows it
+
 Config:
     The config file should be located at '$XDG_CONFIG_HOME/proton.conf' or '$HOME/.config/proton.conf'
     The config requires two values.
     Data: a location to any directory to contain Proton's runtime files.
     Steam: the directory to where steam is installed (the one which contains the steamapps directory).
     Common: the directory to where your proton versions are stored, usually Steam's steamapps/common directory.
+    Every library in Steam's steamapps/libraryfolders.vdf and Steam's compatibilitytools.d are indexed after Common.
+    Tools in compatibilitytools.d can be used by the name declared in their compatibilitytool.vdf.
+    Default Version: optional Proton version to use when none is given, the newest is used otherwise.
+    Cache: optional file to keep the index in, '$XDG_CACHE_HOME/proton/index' or '$HOME/.cache/proton/index' otherwise.
+    Env: optional `[env]` table of environment variables passed to every program.
+    Exe Prefixes: optional, set to true to give every program run without a profile a prefix of its own.
+    Games: optional profiles in `[games.NAME]` tables, used with `--game NAME`. Launchers show their `name`.
+    Prefixes: a game runs in a prefix named after it, or its `prefix`, in the data directory. Other programs
+    share the prefix of their Proton version, 'Proton VERSION', unless `--prefix` or `exe_prefixes` is given.
+    Environment variables are applied in order, later ones win: runtime options, `[env]`, the game's `env`,
+    then `-e`. Variables given to `--unset-env` are removed last. DLL overrides, the game's `dlls` then
+    `--dll`, are merged into the WINEDLLOVERRIDES from the environment, replacing entries for the same DLL.
+    The Proton last run in a prefix is kept in its 'proton-call.toml'. Running it with an older Proton
+    is refused, and before running it with a newer one a copy of it, 'PREFIX@VERSION-TIME', is offered.
+    Proton builds added with `--add` are kept in '$XDG_STATE_HOME/proton/builds.toml' or '$HOME/.local/state/proton/builds.toml'.
     Example:
         data = \"/home/avery/Documents/Proton/env/\"
         steam = \"/home/avery/.steam/steam/\"
         common = \"/home/avery/.steam/steam/steamapps/common/\"
+        default_version = \"8.0\"
+
+        [env]
+        DXVK_LOG_LEVEL = \"none\"
+
+        [games.spaceengine]
+        exe = \"SpaceEngine.exe\"
+        dir = \"/home/avery/Documents/games/SpaceEngine/system\"
+        proton = \"7.0\"
+        prefix = \"spaceengine\"
+        runtime = \"soldier\"
+        options = [\"nvapi\"]
+        env = { DXVK_HUD = \"fps\" }
+        args = [\"-fullscreen\"]
+        winetricks = [\"vcrun2019\", \"d3dcompiler_47\"]
+        registry = [\"tweaks.reg\"]
+        dlls = [\"dinput8=n,b\"]
 ";
 
 #[doc(hidden)]
 fn help() {
-    println!("{}", HELP);
+    println!("{HELP}");
+}
+
+/// Prints every runtime option with its environment variable and description
+fn list_options() {
+    for opt in RuntimeOption::ALL {
+        let name: String = if opt.requires_value() {
+            format!("{}=VALUE", opt.name())
+        } else {
+            opt.name().to_string()
+        };
+
+        println!("{:<22}{:<34}{}", name, opt.to_string(), opt.description());
+    }
 }
 
 #[doc(hidden)]
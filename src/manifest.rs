@@ -0,0 +1,128 @@
+use crate::error::Error;
+use crate::pass;
+use crate::vdf::Vdf;
+use std::path::{Path, PathBuf};
+
+/// Command line of a tool without one in its `toolmanifest.vdf`, that of Proton
+pub const PROTON_COMMANDLINE: &str = "/proton %verb%";
+
+/// Tool declared in a `compatibilitytool.vdf`, as installed into `compatibilitytools.d`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompatTool {
+    /// Internal name Steam uses for the tool
+    pub name: String,
+    /// Name Steam shows for the tool
+    pub display_name: Option<String>,
+    /// Directory the tool is installed in
+    pub path: PathBuf,
+}
+
+impl CompatTool {
+    /// Reads every tool declared in `dir/compatibilitytool.vdf`, `None` if there is no such file
+    ///
+    /// # Errors
+    ///
+    /// Will fail if the file can not be read or parsed
+    pub fn open(dir: &Path) -> Result<Option<Vec<CompatTool>>, Error> {
+        let file: PathBuf = dir.join("compatibilitytool.vdf");
+
+        if !file.is_file() {
+            return pass!(None);
+        }
+
+        let vdf: Vdf = Vdf::open(&file)?;
+        let mut tools: Vec<CompatTool> = Vec::new();
+
+        let compat_tools: Option<&Vdf> = vdf
+            .get("compatibilitytools")
+            .and_then(|v| v.get("compat_tools"));
+
+        for (name, tool) in compat_tools.map(Vdf::entries).unwrap_or_default() {
+            let install_path: &str = tool
+                .get("install_path")
+                .and_then(Vdf::as_str)
+                .unwrap_or(".");
+
+            tools.insert(
+                tools.len(),
+                CompatTool {
+                    name: name.clone(),
+                    display_name: tool
+                        .get("display_name")
+                        .and_then(Vdf::as_str)
+                        .map(str::to_string),
+                    path: dir.join(install_path),
+                },
+            );
+        }
+
+        pass!(Some(tools))
+    }
+}
+
+/// Contents of a tool's `toolmanifest.vdf`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ToolManifest {
+    /// Command line Steam runs the tool with, such as `/proton %verb%`
+    pub commandline: Option<String>,
+    /// App ID of the Steam Linux Runtime the tool runs in
+    pub require_tool_appid: Option<u32>,
+}
+
+impl ToolManifest {
+    /// Reads `dir/toolmanifest.vdf`, `None` if there is no such file
+    ///
+    /// # Errors
+    ///
+    /// Will fail if the file can not be read or parsed
+    pub fn open(dir: &Path) -> Result<Option<ToolManifest>, Error> {
+        let file: PathBuf = dir.join("toolmanifest.vdf");
+
+        if !file.is_file() {
+            return pass!(None);
+        }
+
+        let vdf: Vdf = Vdf::open(&file)?;
+        let manifest: Option<&Vdf> = vdf.get("manifest");
+        let value = |key: &str| manifest.and_then(|m| m.get(key)).and_then(Vdf::as_str);
+
+        pass!(Some(ToolManifest {
+            commandline: value("commandline").map(str::to_string),
+            require_tool_appid: value("require_tool_appid").and_then(|id| id.parse().ok()),
+        }))
+    }
+
+    #[must_use]
+    /// Returns true if the tool in `dir` can be run: the executable of its `commandline`, or else
+    /// its `proton` script, exists. Tools such as Luxtorpeda have no `proton` script
+    pub fn runnable(dir: &Path) -> bool {
+        ToolManifest::open(dir)
+            .ok()
+            .flatten()
+            .unwrap_or_default()
+            .command(dir, "run", PROTON_COMMANDLINE)
+            .first()
+            .is_some_and(|exe| Path::new(exe).is_file())
+    }
+
+    #[must_use]
+    /// Builds the command line of the tool in `dir` for `verb`, from `commandline` or else `default`
+    pub fn command(&self, dir: &Path, verb: &str, default: &str) -> Vec<String> {
+        let commandline: &str = self.commandline.as_deref().unwrap_or(default);
+
+        commandline
+            .split_whitespace()
+            .enumerate()
+            .map(|(i, arg)| {
+                let arg: String = arg.replace("%verb%", verb);
+
+                // the executable is given relative to the tool's directory
+                if i == 0 && arg.starts_with('/') {
+                    format!("{}{}", dir.display(), arg)
+                } else {
+                    arg
+                }
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,217 @@
+/// Resource type of the images of an icon
+pub const RT_ICON: u32 = 3;
+/// Resource type of an icon, a directory of its images by their `RT_ICON` IDs
+pub const RT_GROUP_ICON: u32 = 14;
+
+/// Reads a little endian `u16` at `offset`
+pub fn u16_at(bytes: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(
+        bytes.get(offset..offset.checked_add(2)?)?.try_into().ok()?,
+    ))
+}
+
+/// Reads a little endian `u32` at `offset`
+pub fn u32_at(bytes: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(
+        bytes.get(offset..offset.checked_add(4)?)?.try_into().ok()?,
+    ))
+}
+
+/// Section of a PE file, where its virtual addresses are kept in the file
+#[derive(Debug, Clone, Copy)]
+struct Section {
+    address: u32,
+    size: u32,
+    offset: u32,
+}
+
+/// Resources of a PE file, such as a Windows executable or DLL
+#[derive(Debug)]
+pub struct Resources<'a> {
+    bytes: &'a [u8],
+    sections: Vec<Section>,
+    root: u32,
+}
+
+impl<'a> Resources<'a> {
+    /// Finds the resources in the PE file `bytes`. `None` when it is not a PE file or has no
+    /// resources
+    pub fn parse(bytes: &'a [u8]) -> Option<Resources<'a>> {
+        if bytes.get(..2)? != b"MZ" {
+            return None;
+        }
+
+        let pe: usize = u32_at(bytes, 0x3C)? as usize;
+        if bytes.get(pe..pe + 4)? != b"PE\0\0" {
+            return None;
+        }
+
+        let coff: usize = pe + 4;
+        let sections: usize = u16_at(bytes, coff + 2)?.into();
+        let optional: usize = coff + 20;
+        let optional_size: usize = u16_at(bytes, coff + 16)?.into();
+
+        // data directories follow the fields of PE32 or the wider PE32+
+        let directories: usize = match u16_at(bytes, optional)? {
+            0x10B => optional + 96,
+            0x20B => optional + 112,
+            _ => return None,
+        };
+
+        let count: u32 = u32_at(bytes, directories - 4)?;
+        if count <= 2 {
+            return None;
+        }
+
+        let root: u32 = u32_at(bytes, directories + 2 * 8)?;
+        if root == 0 {
+            return None;
+        }
+
+        let table: usize = optional + optional_size;
+        let sections: Vec<Section> = (0..sections)
+            .map(|i| {
+                let header: usize = table + i * 40;
+                Some(Section {
+                    address: u32_at(bytes, header + 12)?,
+                    size: u32_at(bytes, header + 8)?.max(u32_at(bytes, header + 16)?),
+                    offset: u32_at(bytes, header + 20)?,
+                })
+            })
+            .collect::<Option<Vec<Section>>>()?;
+
+        Some(Resources {
+            bytes,
+            sections,
+            root,
+        })
+    }
+
+    /// Converts the virtual address `rva` into its offset in the file
+    fn offset(&self, rva: u32) -> Option<usize> {
+        let section: &Section = self.sections.iter().find(|s| {
+            rva.checked_sub(s.address)
+                .is_some_and(|within| within < s.size)
+        })?;
+
+        // sections of a malformed file may claim to be kept past the end of any file
+        let offset: u32 = (rva - section.address).checked_add(section.offset)?;
+        Some(offset as usize)
+    }
+
+    /// Reads the entries of the resource directory at `offset` in the resources, as their IDs,
+    /// `None` for named ones, and the offset of what they point to
+    fn directory(&self, offset: u32) -> Option<Vec<(Option<u32>, u32)>> {
+        let dir: usize = self.offset(self.root.checked_add(offset)?)?;
+        let named: usize = u16_at(self.bytes, dir + 12)?.into();
+        let ids: usize = u16_at(self.bytes, dir + 14)?.into();
+
+        (0..named + ids)
+            .map(|i| {
+                let entry: usize = dir + 16 + i * 8;
+                let name: u32 = u32_at(self.bytes, entry)?;
+                let id: Option<u32> = if name & 0x8000_0000 == 0 {
+                    Some(name)
+                } else {
+                    None
+                };
+
+                Some((id, u32_at(self.bytes, entry + 4)?))
+            })
+            .collect()
+    }
+
+    /// Returns every resource of the type `kind`, in the first language it is given in, by its ID,
+    /// `None` for named ones. Named resources come first, then resources by ascending ID, the
+    /// order Windows keeps them in
+    pub fn list(&self, kind: u32) -> Vec<(Option<u32>, &'a [u8])> {
+        // directories are nested by type, then name, then language
+        let types: Vec<(Option<u32>, u32)> = self.directory(0).unwrap_or_default();
+
+        let names: u32 = match types.iter().find(|(id, _)| *id == Some(kind)) {
+            Some((_, names)) if names & 0x8000_0000 != 0 => names & 0x7FFF_FFFF,
+            _ => return Vec::new(),
+        };
+
+        self.directory(names)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|(id, languages)| {
+                if languages & 0x8000_0000 == 0 {
+                    return None;
+                }
+
+                let (_, data) = *self.directory(languages & 0x7FFF_FFFF)?.first()?;
+                if data & 0x8000_0000 != 0 {
+                    return None;
+                }
+
+                let entry: usize = self.offset(self.root.checked_add(data)?)?;
+                let start: usize = self.offset(u32_at(self.bytes, entry)?)?;
+                let size: usize = u32_at(self.bytes, entry + 4)? as usize;
+
+                Some((id, self.bytes.get(start..start.checked_add(size)?)?))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Resources, RT_GROUP_ICON, RT_ICON};
+
+    /// Builds a PE32 file with a single section at `0x1000` kept at `offset`, holding an empty
+    /// resource directory
+    fn pe(offset: u32) -> Vec<u8> {
+        let mut bytes: Vec<u8> = vec![0; 0x300];
+        let mut put = |at: usize, value: &[u8]| bytes[at..at + value.len()].copy_from_slice(value);
+
+        put(0, b"MZ");
+        put(0x3C, &0x40_u32.to_le_bytes());
+        put(0x40, b"PE\0\0");
+        // one section, after the 224 bytes of the optional header
+        put(0x46, &1_u16.to_le_bytes());
+        put(0x54, &0xE0_u16.to_le_bytes());
+        put(0x58, &0x10B_u16.to_le_bytes());
+        // 16 data directories, the resources at 0x1000
+        put(0xB4, &16_u32.to_le_bytes());
+        put(0xC8, &0x1000_u32.to_le_bytes());
+        // the section header
+        put(0x140, &0x100_u32.to_le_bytes());
+        put(0x144, &0x1000_u32.to_le_bytes());
+        put(0x148, &0x100_u32.to_le_bytes());
+        put(0x14C, &offset.to_le_bytes());
+
+        bytes
+    }
+
+    #[test]
+    fn parses_empty_resources() {
+        let bytes: Vec<u8> = pe(0x200);
+        let resources: Resources = Resources::parse(&bytes).unwrap();
+
+        assert_eq!(resources.offset(0x1010), Some(0x210));
+        assert!(resources.list(RT_GROUP_ICON).is_empty());
+    }
+
+    #[test]
+    fn rejects_truncated_files() {
+        let bytes: Vec<u8> = pe(0x200);
+
+        for len in 0..0x200 {
+            let resources: Option<Resources> = Resources::parse(&bytes[..len]);
+            assert!(resources.is_none_or(|resources| resources.list(RT_ICON).is_empty()));
+        }
+    }
+
+    #[test]
+    fn rejects_sections_past_any_file() {
+        let bytes: Vec<u8> = pe(0xFFFF_FF80);
+        let resources: Resources = Resources::parse(&bytes).unwrap();
+
+        assert_eq!(resources.offset(0x1000), Some(0xFFFF_FF80));
+        assert_eq!(resources.offset(0x1080), None);
+        assert_eq!(resources.offset(0x0FFF), None);
+        assert!(resources.list(RT_GROUP_ICON).is_empty());
+    }
+}
@@ -0,0 +1,755 @@
+use crate::error::{Error, Kind};
+use crate::hash::fnv1a;
+use crate::{pass, throw, Version};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{DirEntry, File, FileType, ReadDir};
+use std::os::unix::fs::symlink;
+use std::path::{Component, Path, PathBuf};
+use std::time::SystemTime;
+
+/// Returns the name of the prefix for `exe` when it is run without a profile,
+/// made from its file name and a hash of its full path, such as `Game-5f1c9a2b7e4d0c36`
+#[must_use]
+pub fn exe_name(exe: &Path) -> String {
+    let path: PathBuf = exe.canonicalize().unwrap_or_else(|_| exe.to_path_buf());
+
+    let stem: String = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+
+    let hash: u64 = fnv1a(path.to_string_lossy().as_bytes());
+
+    format!("{stem}-{hash:016x}")
+}
+
+/// Checks `name` can be used as a prefix, which is a directory in the data directory
+///
+/// # Errors
+///
+/// Will fail if `name` is empty, `.` or `..`, or contains a `/`
+pub fn check_name(name: &str) -> Result<(), Error> {
+    if name.is_empty() || name == "." || name == ".." || name.contains(['/', '\0']) {
+        throw!(Kind::PrefixName, "'{}'", name);
+    }
+
+    pass!()
+}
+
+/// Wine prefix in the data directory, as created by `Proton::run`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Prefix {
+    name: String,
+    path: PathBuf,
+}
+
+impl Prefix {
+    /// Opens the prefix `name` in `data`
+    ///
+    /// # Errors
+    ///
+    /// Will fail if `name` is not a valid prefix name, or there is no such prefix
+    pub fn open(data: &Path, name: &str) -> Result<Prefix, Error> {
+        check_name(name)?;
+
+        let path: PathBuf = data.join(name);
+
+        if !path.is_dir() {
+            throw!(Kind::PrefixMissing, "'{}'", name);
+        }
+
+        pass!(Prefix {
+            name: name.to_string(),
+            path,
+        })
+    }
+
+    /// Lists every prefix in `data`, sorted by name
+    ///
+    /// # Errors
+    ///
+    /// Will fail if `data` can not be read
+    pub fn list(data: &Path) -> Result<Vec<Prefix>, Error> {
+        let rd: ReadDir = match data.read_dir() {
+            Ok(rd) => rd,
+            Err(e) => throw!(Kind::PrefixIo, "'{}': {}", data.display(), e),
+        };
+
+        let mut prefixes: Vec<Prefix> = rd
+            .flatten()
+            .filter(|entry| entry.path().is_dir())
+            .map(|entry| Prefix {
+                name: entry.file_name().to_string_lossy().to_string(),
+                path: entry.path(),
+            })
+            .collect();
+
+        prefixes.sort_by(|a, b| a.name.cmp(&b.name));
+
+        pass!(prefixes)
+    }
+
+    #[must_use]
+    #[inline]
+    /// Returns the name of the prefix
+    pub fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    #[must_use]
+    #[inline]
+    /// Returns the directory of the prefix
+    pub fn path(&self) -> PathBuf {
+        self.path.clone()
+    }
+
+    #[must_use]
+    /// Returns the size of every file in the prefix, in bytes
+    pub fn size(&self) -> u64 {
+        fn size_of(dir: &Path) -> u64 {
+            let Ok(rd) = dir.read_dir() else {
+                return 0;
+            };
+
+            rd.flatten()
+                .map(|entry| match entry.metadata() {
+                    Ok(meta) if meta.is_dir() => size_of(&entry.path()),
+                    Ok(meta) => meta.len(),
+                    Err(_) => 0,
+                })
+                .sum()
+        }
+
+        size_of(&self.path)
+    }
+
+    #[must_use]
+    /// Returns when the prefix was created, if the file system records it
+    pub fn created(&self) -> Option<SystemTime> {
+        self.path.metadata().and_then(|m| m.created()).ok()
+    }
+
+    #[must_use]
+    /// Returns the build of the Proton which last ran in the prefix, from the `config_info` Proton writes
+    pub fn proton(&self) -> Option<String> {
+        let info: String = std::fs::read_to_string(self.path.join("config_info")).ok()?;
+        info.lines()
+            .next()
+            .and_then(|line| line.split_whitespace().last())
+            .map(str::to_string)
+    }
+
+    #[must_use]
+    /// Returns the version of Proton which last ran in the prefix, as recorded in its metadata
+    pub fn recorded_version(&self) -> Option<Version> {
+        PrefixMeta::open(self).ok()?.proton?.parse().ok()
+    }
+
+    #[must_use]
+    /// Returns the version of the prefix layout, from the `version` file Proton writes
+    pub fn prefix_version(&self) -> Option<String> {
+        let version: String = std::fs::read_to_string(self.path.join("version")).ok()?;
+        let version: &str = version.trim();
+
+        if version.is_empty() {
+            None
+        } else {
+            Some(version.to_string())
+        }
+    }
+
+    /// Copies the prefix into a new prefix `name`, keeping symbolic links. Links into the prefix
+    /// are pointed into the copy
+    ///
+    /// # Errors
+    ///
+    /// Will fail if `name` is not a valid prefix name, already exists, or copying fails
+    pub fn clone_to(&self, name: &str) -> Result<Prefix, Error> {
+        check_name(name)?;
+
+        let data: &Path = self.path.parent().unwrap_or(&self.path);
+        let path: PathBuf = data.join(name);
+
+        if path.exists() {
+            throw!(Kind::PrefixExists, "'{}'", name);
+        }
+
+        if let Err(e) = copy_dir(&self.path, &path, &self.path, &path) {
+            // leave no half copied prefix behind
+            let _ = std::fs::remove_dir_all(&path);
+            throw!(Kind::PrefixIo, "'{}': {}", path.display(), e);
+        }
+
+        pass!(Prefix {
+            name: name.to_string(),
+            path,
+        })
+    }
+
+    /// Archives the prefix into `file` as a zstd compressed tar, keeping permissions and symbolic
+    /// links. Links into the prefix are stored relative to the link, so a restored prefix can move.
+    /// Returns the number of entries archived
+    ///
+    /// # Errors
+    ///
+    /// Will fail if the prefix can not be read, or `file` can not be written
+    pub fn backup(&self, file: &Path, filter: &PrefixFilter) -> Result<usize, Error> {
+        // write next to `file` first, so a failed backup never replaces a good one
+        let part: PathBuf = file.with_extension("part");
+
+        let count: usize = match self.write_archive(&part, filter) {
+            Ok(count) => count,
+            Err(e) => {
+                let _ = std::fs::remove_file(&part);
+                throw!(Kind::PrefixArchive, "'{}': {}", file.display(), e);
+            }
+        };
+
+        if let Err(e) = std::fs::rename(&part, file) {
+            let _ = std::fs::remove_file(&part);
+            throw!(Kind::PrefixArchive, "'{}': {}", file.display(), e);
+        }
+
+        pass!(count)
+    }
+
+    fn write_archive(&self, file: &Path, filter: &PrefixFilter) -> std::io::Result<usize> {
+        let encoder = zstd::Encoder::new(File::create(file)?, 0)?;
+        let mut builder: tar::Builder<_> = tar::Builder::new(encoder);
+        builder.follow_symlinks(false);
+
+        let top: PathBuf = PathBuf::from(&self.name);
+        builder.append_dir(&top, &self.path)?;
+
+        let count: usize = append_dir(&mut builder, &self.path, Path::new(""), &top, filter)?;
+
+        builder.into_inner()?.finish()?.sync_all()?;
+        Ok(count + 1)
+    }
+
+    /// Restores the archive `file`, made by `backup`, as the prefix `name` in `data`, or the name it
+    /// was backed up with. An existing prefix is only replaced if `replace`
+    ///
+    /// # Errors
+    ///
+    /// Will fail if `file` is not a prefix backup, the prefix exists and not `replace`, or
+    /// writing the prefix fails
+    pub fn restore(
+        data: &Path,
+        file: &Path,
+        name: Option<&str>,
+        filter: &PrefixFilter,
+        replace: bool,
+    ) -> Result<Prefix, Error> {
+        if let Some(name) = name {
+            check_name(name)?;
+            if data.join(name).exists() && !replace {
+                throw!(Kind::PrefixExists, "'{}'", name);
+            }
+        }
+
+        // unpack beside the prefix and swap it in once complete, leaving any existing prefix
+        // untouched if the archive is broken
+        let temp: PathBuf = data.join(format!(".restore-{}", std::process::id()));
+
+        let top: String = match read_archive(file, &temp, filter) {
+            Ok(top) => top,
+            Err(e) => {
+                let _ = std::fs::remove_dir_all(&temp);
+                throw!(Kind::PrefixArchive, "'{}': {}", file.display(), e);
+            }
+        };
+
+        let name: String = name.map_or(top, str::to_string);
+        let path: PathBuf = data.join(&name);
+
+        let swapped: Result<(), Error> = (|| {
+            check_name(&name)?;
+
+            if path.exists() {
+                if !replace {
+                    throw!(Kind::PrefixExists, "'{}'", name);
+                }
+
+                if let Err(e) = std::fs::remove_dir_all(&path) {
+                    throw!(Kind::PrefixIo, "'{}': {}", path.display(), e);
+                }
+            }
+
+            if let Err(e) = std::fs::rename(&temp, &path) {
+                throw!(Kind::PrefixIo, "'{}': {}", path.display(), e);
+            }
+
+            pass!()
+        })();
+
+        if let Err(e) = swapped {
+            let _ = std::fs::remove_dir_all(&temp);
+            return Err(e);
+        }
+
+        pass!(Prefix { name, path })
+    }
+
+    /// Deletes the prefix and everything in it
+    ///
+    /// # Errors
+    ///
+    /// Will fail if the prefix can not be removed
+    pub fn remove(self) -> Result<(), Error> {
+        if let Err(e) = std::fs::remove_dir_all(&self.path) {
+            throw!(Kind::PrefixIo, "'{}': {}", self.path.display(), e);
+        }
+
+        pass!()
+    }
+}
+
+/// Metadata `proton-call` keeps in a prefix, in `proton-call.toml`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PrefixMeta {
+    proton: Option<String>,
+    #[serde(default)]
+    winetricks: Vec<String>,
+    // tables are written after values, and only when they have something in them
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    registry: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    switches: Vec<Switch>,
+}
+
+/// Change of the Proton version run in a prefix
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Switch {
+    /// Version of Proton run in the prefix before
+    pub from: String,
+    /// Version of Proton run in the prefix since
+    pub to: String,
+    /// Seconds since the Unix epoch when the version changed
+    pub time: u64,
+    /// Prefix the prefix was copied into before the change
+    pub snapshot: Option<String>,
+}
+
+impl PrefixMeta {
+    /// Opens the metadata of `prefix`. A prefix without metadata starts from the build in the
+    /// `config_info` Proton writes
+    ///
+    /// # Errors
+    ///
+    /// Will fail if the metadata can not be read or parsed
+    pub fn open(prefix: &Prefix) -> Result<PrefixMeta, Error> {
+        let path: PathBuf = PrefixMeta::location(prefix);
+
+        if !path.exists() {
+            let proton: Option<String> = prefix.proton().map(|build| {
+                build
+                    .parse::<Version>()
+                    .map_or(build, |version| version.to_string())
+            });
+
+            return pass!(PrefixMeta {
+                proton,
+                ..PrefixMeta::default()
+            });
+        }
+
+        match std::fs::read_to_string(&path) {
+            Ok(s) => match toml::from_str(&s) {
+                Ok(meta) => pass!(meta),
+                Err(e) => throw!(Kind::PrefixIo, "'{}': {}", path.display(), e),
+            },
+            Err(e) => throw!(Kind::PrefixIo, "'{}': {}", path.display(), e),
+        }
+    }
+
+    /// Saves the metadata into `prefix`
+    ///
+    /// # Errors
+    ///
+    /// Will fail if the metadata can not be written
+    pub fn save(&self, prefix: &Prefix) -> Result<(), Error> {
+        let path: PathBuf = PrefixMeta::location(prefix);
+
+        let s: String = match toml::to_string(self) {
+            Ok(s) => s,
+            Err(e) => throw!(Kind::PrefixIo, "{}", e),
+        };
+
+        if let Err(e) = std::fs::write(&path, s) {
+            throw!(Kind::PrefixIo, "'{}': {}", path.display(), e);
+        }
+
+        pass!()
+    }
+
+    fn location(prefix: &Prefix) -> PathBuf {
+        prefix.path.join("proton-call.toml")
+    }
+
+    #[must_use]
+    #[inline]
+    /// Returns every change of the Proton version run in the prefix, oldest first
+    pub fn switches(&self) -> Vec<Switch> {
+        self.switches.clone()
+    }
+
+    #[must_use]
+    #[inline]
+    /// Returns the winetricks verbs applied to the prefix, in the order they were applied
+    pub fn winetricks(&self) -> Vec<String> {
+        self.winetricks.clone()
+    }
+
+    /// Records the winetricks `verb` as applied to the prefix
+    pub fn record_winetricks(&mut self, verb: &str) {
+        if !self.winetricks.iter().any(|v| v == verb) {
+            self.winetricks
+                .insert(self.winetricks.len(), verb.to_string());
+        }
+    }
+
+    #[must_use]
+    #[inline]
+    /// Returns the registry files imported into the prefix, with the hash of their contents
+    pub fn registry(&self) -> BTreeMap<String, String> {
+        self.registry.clone()
+    }
+
+    #[must_use]
+    /// Returns true if a registry file with the contents hashed as `hash` was imported into the prefix
+    pub fn has_registry(&self, hash: &str) -> bool {
+        self.registry.values().any(|h| h == hash)
+    }
+
+    /// Records the registry `file`, with the contents hashed as `hash`, as imported into the prefix
+    pub fn record_registry(&mut self, file: &Path, hash: &str) {
+        self.registry
+            .insert(file.to_string_lossy().to_string(), hash.to_string());
+    }
+
+    /// Records `version` as run in the prefix, and the switch from the version run before if it
+    /// changed to another release
+    pub fn record(&mut self, version: Version, snapshot: Option<String>) {
+        let same: bool = self
+            .proton
+            .as_ref()
+            .and_then(|from| from.parse::<Version>().ok())
+            .is_some_and(|from| from.is_same_release(version));
+
+        let version: String = version.to_string();
+
+        if let Some(from) = &self.proton {
+            if *from != version && !same {
+                let time: u64 = SystemTime::now()
+                    .duration_since(SystemTime::UNIX_EPOCH)
+                    .map_or(0, |d| d.as_secs());
+
+                self.switches.insert(
+                    self.switches.len(),
+                    Switch {
+                        from: from.clone(),
+                        to: version.clone(),
+                        time,
+                        snapshot,
+                    },
+                );
+            }
+        }
+
+        self.proton = Some(version);
+    }
+}
+
+/// Paths inside a prefix to back up or restore. Paths are relative to the prefix, and may also
+/// be given relative to its `pfx` directory, such as `drive_c/windows`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PrefixFilter {
+    include: Vec<PathBuf>,
+    exclude: Vec<PathBuf>,
+}
+
+impl PrefixFilter {
+    #[must_use]
+    /// Creates a filter keeping only what is under `include`, every path if empty, and nothing
+    /// under `exclude`
+    pub fn new(include: Vec<PathBuf>, exclude: Vec<PathBuf>) -> PrefixFilter {
+        PrefixFilter { include, exclude }
+    }
+
+    #[must_use]
+    /// Returns true if `path`, relative to the prefix, is kept. Directories leading to an
+    /// included path are kept too
+    pub fn keeps(&self, path: &Path, is_dir: bool) -> bool {
+        let under = |pattern: &PathBuf| {
+            path.starts_with(pattern)
+                || path
+                    .strip_prefix("pfx")
+                    .is_ok_and(|rest| rest.starts_with(pattern))
+        };
+
+        let leads_to = |pattern: &PathBuf| {
+            pattern.starts_with(path)
+                || path
+                    .strip_prefix("pfx")
+                    .is_ok_and(|rest| pattern.starts_with(rest))
+        };
+
+        if self.exclude.iter().any(under) {
+            return false;
+        }
+
+        self.include.is_empty()
+            || self.include.iter().any(under)
+            || (is_dir && self.include.iter().any(leads_to))
+    }
+}
+
+/// Appends everything in `dir` kept by `filter` to `builder` under `top`. `rel` is the path
+/// of `dir` inside the prefix. Returns the number of entries appended
+fn append_dir<W: std::io::Write>(
+    builder: &mut tar::Builder<W>,
+    dir: &Path,
+    rel: &Path,
+    top: &Path,
+    filter: &PrefixFilter,
+) -> std::io::Result<usize> {
+    let mut entries: Vec<DirEntry> = dir.read_dir()?.collect::<std::io::Result<_>>()?;
+    entries.sort_by_key(DirEntry::file_name);
+
+    let mut count: usize = 0;
+
+    for entry in entries {
+        let kind: FileType = entry.file_type()?;
+        let rel: PathBuf = rel.join(entry.file_name());
+
+        if !filter.keeps(&rel, kind.is_dir()) {
+            continue;
+        }
+
+        let name: PathBuf = top.join(&rel);
+
+        if kind.is_symlink() {
+            let target: PathBuf = std::fs::read_link(entry.path())?;
+            let target: PathBuf = relative_link(&target, &rel, dir);
+
+            let mut header: tar::Header = tar::Header::new_gnu();
+            header.set_metadata(&entry.path().symlink_metadata()?);
+            header.set_entry_type(tar::EntryType::Symlink);
+            header.set_size(0);
+            builder.append_link(&mut header, &name, &target)?;
+        } else if kind.is_dir() {
+            builder.append_dir(&name, entry.path())?;
+            count += append_dir(builder, &entry.path(), &rel, top, filter)?;
+        } else if kind.is_file() {
+            builder.append_path_with_name(entry.path(), &name)?;
+        } else {
+            // sockets, pipes and devices have no place in a backup
+            continue;
+        }
+
+        count += 1;
+    }
+
+    Ok(count)
+}
+
+/// Makes `target`, of the link at `rel` in the prefix, relative to the link when it points
+/// into the prefix, which is `dir` with `rel`'s parent removed
+fn relative_link(target: &Path, rel: &Path, dir: &Path) -> PathBuf {
+    let depth: usize = rel.components().count() - 1;
+    let root: &Path = dir.ancestors().nth(depth).unwrap_or(dir);
+
+    match target.strip_prefix(root) {
+        Ok(rest) if target.is_absolute() => {
+            let mut relative: PathBuf = PathBuf::new();
+            for _ in 0..depth {
+                relative.push("..");
+            }
+            relative.join(rest)
+        }
+        _ => target.to_path_buf(),
+    }
+}
+
+/// Unpacks the prefix backup `file` into `to`, keeping what `filter` keeps. Returns the name
+/// the prefix was backed up with
+fn read_archive(file: &Path, to: &Path, filter: &PrefixFilter) -> std::io::Result<String> {
+    let invalid = |msg: &str| std::io::Error::new(std::io::ErrorKind::InvalidData, msg.to_string());
+
+    let decoder = zstd::Decoder::new(File::open(file)?)?;
+    let mut archive: tar::Archive<_> = tar::Archive::new(decoder);
+    archive.set_preserve_permissions(true);
+    archive.set_preserve_mtime(true);
+
+    std::fs::create_dir(to)?;
+
+    let mut top: Option<String> = None;
+
+    for entry in archive.entries()? {
+        let mut entry: tar::Entry<_> = entry?;
+        let path: PathBuf = entry.path()?.to_path_buf();
+
+        let mut components = path.components();
+        let name: String = match components.next() {
+            Some(Component::Normal(name)) => name.to_string_lossy().to_string(),
+            _ => return Err(invalid("not a prefix backup")),
+        };
+
+        match &top {
+            Some(top) if *top != name => return Err(invalid("more than one prefix in backup")),
+            Some(_) => {}
+            None => top = Some(name),
+        }
+
+        let rel: &Path = components.as_path();
+
+        if rel.as_os_str().is_empty() {
+            continue;
+        }
+
+        // an entry must never be written outside of the prefix
+        if !rel.components().all(|c| matches!(c, Component::Normal(_))) {
+            return Err(invalid("path leaves the prefix"));
+        }
+
+        // backups never hold hard links, and one may be made to any file outside of the prefix
+        if entry.header().entry_type().is_hard_link() {
+            return Err(invalid("hard link in backup"));
+        }
+
+        if !filter.keeps(rel, entry.header().entry_type().is_dir()) {
+            continue;
+        }
+
+        // nor through a link unpacked before it, which may point anywhere
+        let mut ancestor: PathBuf = to.to_path_buf();
+        for component in rel.parent().into_iter().flat_map(Path::components) {
+            ancestor.push(component);
+            if ancestor
+                .symlink_metadata()
+                .is_ok_and(|m| m.file_type().is_symlink())
+            {
+                return Err(invalid("path leaves the prefix through a link"));
+            }
+        }
+
+        let dest: PathBuf = to.join(rel);
+        if let Some(parent) = dest.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        entry.unpack(&dest)?;
+    }
+
+    top.ok_or_else(|| invalid("empty backup"))
+}
+
+/// Copies `from` into `to`, recreating symbolic links. Links pointing into `root` are pointed
+/// into the same place under `copy`, the root of the copy
+fn copy_dir(from: &Path, to: &Path, root: &Path, copy: &Path) -> std::io::Result<()> {
+    std::fs::create_dir(to)?;
+
+    for entry in from.read_dir()? {
+        let entry: DirEntry = entry?;
+        let kind: FileType = entry.file_type()?;
+        let dest: PathBuf = to.join(entry.file_name());
+
+        if kind.is_symlink() {
+            let target: PathBuf = std::fs::read_link(entry.path())?;
+            let target: PathBuf = match target.strip_prefix(root) {
+                Ok(rest) => copy.join(rest),
+                Err(_) => target,
+            };
+            symlink(target, &dest)?;
+        } else if kind.is_dir() {
+            copy_dir(&entry.path(), &dest, root, copy)?;
+        } else {
+            std::fs::copy(entry.path(), &dest)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{read_archive, PrefixFilter};
+    use std::fs::File;
+    use std::path::{Path, PathBuf};
+
+    /// Directory in the temporary directory, removed with everything in it when dropped
+    struct TempDir {
+        path: PathBuf,
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.path);
+        }
+    }
+
+    /// Writes a backup of the prefix `game` holding a link `game/link` of the kind `kind` to
+    /// `target`, then the file `file` with the contents `owned`
+    fn backup(archive: &Path, kind: tar::EntryType, target: &Path, file: &str) {
+        let encoder = zstd::Encoder::new(File::create(archive).unwrap(), 0).unwrap();
+        let mut builder: tar::Builder<_> = tar::Builder::new(encoder);
+
+        let mut header: tar::Header = tar::Header::new_gnu();
+        header.set_entry_type(kind);
+        header.set_size(0);
+        builder
+            .append_link(&mut header, "game/link", target)
+            .unwrap();
+
+        let mut header: tar::Header = tar::Header::new_gnu();
+        header.set_size(5);
+        header.set_mode(0o644);
+        builder
+            .append_data(&mut header, file, &b"owned"[..])
+            .unwrap();
+
+        builder.into_inner().unwrap().finish().unwrap();
+    }
+
+    #[test]
+    fn restore_stays_in_prefix() {
+        let dir: TempDir = TempDir {
+            path: std::env::temp_dir().join(format!("proton-call-{}-restore", std::process::id())),
+        };
+        let outside: PathBuf = dir.path.join("outside");
+        let secret: PathBuf = outside.join("secret.txt");
+        let file: PathBuf = dir.path.join("backup.tar.zst");
+        std::fs::create_dir_all(&outside).unwrap();
+        std::fs::write(&secret, "secret").unwrap();
+
+        // a file written through a link to outside of the prefix
+        backup(
+            &file,
+            tar::EntryType::Symlink,
+            &outside,
+            "game/link/owned.txt",
+        );
+        let result = read_archive(&file, &dir.path.join("symlink"), &PrefixFilter::default());
+
+        assert!(result.is_err());
+        assert!(!outside.join("owned.txt").exists());
+
+        // a file of the prefix which is a file outside of it, then written over
+        backup(&file, tar::EntryType::Link, &secret, "game/link");
+        let result = read_archive(&file, &dir.path.join("hard"), &PrefixFilter::default());
+
+        assert!(result.is_err());
+        assert!(!dir.path.join("hard/link").exists());
+        assert_eq!(std::fs::read_to_string(&secret).unwrap(), "secret");
+    }
+}
@@ -0,0 +1,133 @@
+use crate::{DllOverride, RunTimeVersion, RuntimeSetting};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// Profile type for a game defined in the `[games.<name>]` table of the config
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Profile {
+    name: Option<String>,
+    #[serde(default)]
+    wrapper: Vec<String>,
+    exe: PathBuf,
+    dir: Option<PathBuf>,
+    proton: Option<String>,
+    prefix: Option<String>,
+    runtime: Option<RunTimeVersion>,
+    #[serde(default)]
+    options: Vec<RuntimeSetting>,
+    #[serde(default)]
+    env: BTreeMap<String, String>,
+    #[serde(default)]
+    args: Vec<String>,
+    #[serde(default)]
+    winetricks: Vec<String>,
+    #[serde(default)]
+    registry: Vec<PathBuf>,
+    #[serde(default)]
+    dlls: Vec<DllOverride>,
+}
+
+impl Profile {
+    #[must_use]
+    #[inline]
+    /// Returns the name the game is shown with in launchers
+    pub fn name(&self) -> Option<String> {
+        self.name.clone()
+    }
+
+    #[must_use]
+    #[inline]
+    /// Returns the command launchers run `proton-call` with, such as `prime-run`
+    pub fn wrapper(&self) -> Vec<String> {
+        self.wrapper.clone()
+    }
+
+    #[must_use]
+    /// Returns the executable of the game, relative paths are taken from `dir`
+    pub fn exe(&self) -> PathBuf {
+        match &self.dir {
+            Some(dir) if self.exe.is_relative() => dir.join(&self.exe),
+            _ => self.exe.clone(),
+        }
+    }
+
+    #[must_use]
+    #[inline]
+    /// Returns the working directory of the game
+    pub fn dir(&self) -> Option<PathBuf> {
+        self.dir.clone()
+    }
+
+    #[must_use]
+    #[inline]
+    /// Returns the Proton version or added build name of the game
+    pub fn proton(&self) -> Option<String> {
+        self.proton.clone()
+    }
+
+    #[must_use]
+    #[inline]
+    /// Returns the prefix of the game, when it is not named after the game
+    pub fn prefix(&self) -> Option<String> {
+        self.prefix.clone()
+    }
+
+    #[must_use]
+    #[inline]
+    /// Returns the runtime of the game
+    pub fn runtime(&self) -> Option<RunTimeVersion> {
+        self.runtime
+    }
+
+    #[must_use]
+    #[inline]
+    /// Returns the runtime options of the game
+    pub fn options(&self) -> Vec<RuntimeSetting> {
+        self.options.clone()
+    }
+
+    #[must_use]
+    /// Returns the environment variables of the game
+    pub fn env(&self) -> Vec<(String, String)> {
+        self.env
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect()
+    }
+
+    #[must_use]
+    #[inline]
+    /// Returns the arguments passed to the game
+    pub fn args(&self) -> Vec<String> {
+        self.args.clone()
+    }
+
+    #[must_use]
+    #[inline]
+    /// Returns the winetricks verbs applied to the game's prefix before it runs
+    pub fn winetricks(&self) -> Vec<String> {
+        self.winetricks.clone()
+    }
+
+    #[must_use]
+    #[inline]
+    /// Returns the DLL overrides of the game
+    pub fn dlls(&self) -> Vec<DllOverride> {
+        self.dlls.clone()
+    }
+
+    #[must_use]
+    /// Returns the registry files imported into the game's prefix before it runs, relative paths
+    /// are taken from `dir`
+    pub fn registry(&self) -> Vec<PathBuf> {
+        self.registry
+            .iter()
+            .map(|file| match &self.dir {
+                Some(dir) if file.is_relative() => dir.join(file),
+                _ => file.clone(),
+            })
+            .collect()
+    }
+}
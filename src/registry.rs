@@ -0,0 +1,79 @@
+use crate::error::{Error, Kind};
+use crate::hash::fnv1a;
+use crate::{pass, throw};
+use std::path::{Path, PathBuf};
+
+/// Headers a `.reg` file starts with, for Windows 95 and for Windows 2000 and newer
+const HEADERS: [&str; 2] = ["REGEDIT4", "Windows Registry Editor Version 5.00"];
+
+/// Registry file to import into a prefix with `regedit`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegFile {
+    path: PathBuf,
+    hash: String,
+}
+
+impl RegFile {
+    /// Reads the `.reg` file at `path` and hashes its contents
+    ///
+    /// # Errors
+    ///
+    /// Will fail if the file can not be read, or does not start with a registry header
+    pub fn open(path: &Path) -> Result<RegFile, Error> {
+        let path: PathBuf = match path.canonicalize() {
+            Ok(path) => path,
+            Err(e) => throw!(Kind::Registry, "'{}': {}", path.display(), e),
+        };
+
+        let bytes: Vec<u8> = match std::fs::read(&path) {
+            Ok(bytes) => bytes,
+            Err(e) => throw!(Kind::Registry, "'{}': {}", path.display(), e),
+        };
+
+        // regedit writes UTF-16 with a byte order mark, hand written files are usually UTF-8
+        let text: String = if let Some(utf16) = bytes.strip_prefix(&[0xFF, 0xFE]) {
+            let units: Vec<u16> = utf16
+                .chunks_exact(2)
+                .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+                .collect();
+            String::from_utf16_lossy(&units)
+        } else {
+            let utf8: &[u8] = bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]).unwrap_or(&bytes);
+            String::from_utf8_lossy(utf8).to_string()
+        };
+
+        if !HEADERS.iter().any(|header| text.starts_with(header)) {
+            throw!(
+                Kind::Registry,
+                "'{}' is not a registry file, it should start with '{}'",
+                path.display(),
+                HEADERS[1]
+            );
+        }
+
+        pass!(RegFile {
+            hash: format!("{:016x}", fnv1a(&bytes)),
+            path,
+        })
+    }
+
+    #[must_use]
+    #[inline]
+    /// Returns the absolute path of the file
+    pub fn path(&self) -> PathBuf {
+        self.path.clone()
+    }
+
+    #[must_use]
+    #[inline]
+    /// Returns the hash of the file's contents, as hex
+    pub fn hash(&self) -> String {
+        self.hash.clone()
+    }
+
+    #[must_use]
+    /// Returns the path of the file as Wine sees it, through the `Z:` drive Proton maps to `/`
+    pub fn wine_path(&self) -> String {
+        format!("Z:{}", self.path.to_string_lossy().replace('/', "\\"))
+    }
+}
@@ -2,72 +2,85 @@ use std::{
     convert::Infallible,
     fmt::Display,
     path::{Path, PathBuf},
-    process::ExitStatus,
     str::FromStr,
 };
 
 use crate::{
     error::{Error, Kind},
-    pass, throw, Proton,
+    manifest::ToolManifest,
+    pass, throw, Proton, Verb,
 };
 
 #[derive(Debug)]
 pub struct Runtime {
     version: RunTimeVersion,
     path: PathBuf,
-    proton: Proton,
+    manifest: ToolManifest,
 }
 
 impl Runtime {
-    pub fn from_proton(version: RunTimeVersion, proton: Proton) -> Result<Self, Error> {
-        Ok(Self {
-            version,
-            path: Self::find(&proton.common, version)?,
-            proton,
-        })
-    }
-
-    pub fn execute(self) -> Result<ExitStatus, Error> {
-        use std::process::{Child, Command};
-
-        let envs: Vec<(String, String)> = self.proton.gen_options();
-
-        let mut child: Child = match Command::new(&self.path)
-        .arg(&self.proton.path)
-        .arg("runinprefix")
-        .arg(&self.proton.program)
-        .args(&self.proton.args)
-        .env("STEAM_COMPAT_DATA_PATH", &self.proton.compat)
-        .env("STEAM_COMPAT_CLIENT_INSTALL_PATH", &self.proton.steam)
-        .envs(envs)
-        .spawn() {
-            Ok(child) => child,
-            Err(e) => throw!(Kind::ProtonExit, "{}", e),
+    /// Finds the runtime for `proton`: `version` when given, otherwise the runtime
+    /// required in Proton's `toolmanifest.vdf`. `None` when Proton needs no runtime
+    pub fn from_proton(
+        version: Option<RunTimeVersion>,
+        proton: &Proton,
+    ) -> Result<Option<Self>, Error> {
+        let version: RunTimeVersion = match version {
+            Some(version) => version,
+            None => match proton.manifest.require_tool_appid {
+                Some(appid) => match RunTimeVersion::from_appid(appid) {
+                    Some(version) => version,
+                    None => throw!(Kind::RuntimeMissing, "unknown runtime app ID {}", appid),
+                },
+                None => return pass!(None),
+            },
         };
 
+        let path: PathBuf = Self::find(proton, version)?;
+        let manifest: ToolManifest = ToolManifest::open(&path)?.unwrap_or_default();
 
-        let status: ExitStatus = match child.wait() {
-            Ok(e) => e,
-            Err(e) => throw!(Kind::ProtonWait, "'{}': {}", child.id(), e),
-        };
+        pass!(Some(Self {
+            version,
+            path,
+            manifest,
+        }))
+    }
 
-        pass!(status)
+    /// Returns the version of the runtime
+    pub fn version(&self) -> RunTimeVersion {
+        self.version
     }
 
-    pub fn find(common: &Path, version: RunTimeVersion) -> Result<PathBuf, Error> {
-        let tmp = format!("{}/{}/run", common.display(), version);
-        let path = PathBuf::from(tmp);
+    /// Builds the command line Proton is run in with `verb`, as declared in the runtime's `toolmanifest.vdf`
+    pub fn command(&self, verb: Verb) -> Vec<String> {
+        self.manifest.command(&self.path, verb.name(), "/run")
+    }
+
+    /// Finds the runtime in the library of Proton, or else in the libraries of Proton, such as
+    /// those of the index, or else in `common`
+    pub fn find(proton: &Proton, version: RunTimeVersion) -> Result<PathBuf, Error> {
+        let library: Option<&Path> = proton.tool_dir().parent();
+        let libraries = proton.libraries.iter().map(PathBuf::as_path);
+
+        for dir in library
+            .into_iter()
+            .chain(libraries)
+            .chain([proton.common.as_path()])
+        {
+            let path: PathBuf = dir.join(version.to_string());
 
-        if path.exists() {
-            pass!(path)
-        } else {
-            throw!(Kind::RuntimeMissing, "{}", version)
+            if path.join("toolmanifest.vdf").exists() || path.join("run").exists() {
+                return pass!(path);
+            }
         }
+
+        throw!(Kind::RuntimeMissing, "{}", version)
     }
 }
 
 /// Enum to represet Steam runtime versions
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, serde::Deserialize)]
+#[serde(from = "String")]
 pub enum RunTimeVersion {
     /// Default version of Steam's runtime
     Default,
@@ -75,12 +88,25 @@ pub enum RunTimeVersion {
     Sniper,
     /// Soldier version of Steam's runtime
     Soldier,
-    /// BattleEye version of Steam's runtime
+    /// `BattleEye` version of Steam's runtime
     BattleEye,
-    /// EasyAntiCheat version of Steam's runtime
+    /// `EasyAntiCheat` version of Steam's runtime
     EasyAntiCheat,
 }
 
+impl RunTimeVersion {
+    #[must_use]
+    /// Converts the App ID of a Steam runtime, as used in `require_tool_appid`, into its version
+    pub fn from_appid(appid: u32) -> Option<RunTimeVersion> {
+        match appid {
+            1_070_560 => Some(RunTimeVersion::Default),
+            1_391_110 => Some(RunTimeVersion::Soldier),
+            1_628_350 => Some(RunTimeVersion::Sniper),
+            _ => None,
+        }
+    }
+}
+
 impl Display for RunTimeVersion {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -96,6 +122,7 @@ impl Display for RunTimeVersion {
 impl FromStr for RunTimeVersion {
     type Err = Infallible;
 
+    #[allow(clippy::match_same_arms)]
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         Ok(match s {
             "default" => Self::Default,
@@ -107,3 +134,12 @@ impl FromStr for RunTimeVersion {
         })
     }
 }
+
+impl From<String> for RunTimeVersion {
+    fn from(s: String) -> Self {
+        match s.parse() {
+            Ok(version) => version,
+            Err(e) => match e {},
+        }
+    }
+}
@@ -3,14 +3,25 @@ use std::{
     str::FromStr,
 };
 
-use crate::{error::Kind, throw};
+use crate::{error::Kind, pass, throw};
 
-/// Runtime options define at https://github.com/ValveSoftware/Proton/tree/proton_6.3-rc#runtime-config-options
+/// Runtime options define at <https://github.com/ValveSoftware/Proton#runtime-config-options>
 #[allow(non_camel_case_types)]
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, serde::Deserialize)]
+#[serde(try_from = "String")]
 pub enum RuntimeOption {
     /// Convenience method for dumping a useful debug log
     log, // PROTON_LOG
+    /// Output log files into the directory given, instead of the home directory
+    logdir, // PROTON_LOG_DIR
+    /// Write useful debug scripts for the game into `$PROTON_DEBUG_DIR/proton_$USER/`
+    dumpdebugcommands, // PROTON_DUMP_DEBUG_COMMANDS
+    /// Root directory for the debug scripts, `/tmp` by default
+    debugdir, // PROTON_DEBUG_DIR
+    /// Write crash logs into the directory given
+    crashreportdir, // PROTON_CRASH_REPORT_DIR
+    /// Wait for a debugger to attach to the game process before running it
+    waitattach, // PROTON_WAIT_ATTACH
     /// Use OpenGL-based wined3d instead of Vulkan-based DXVK for d3d11, d3d10, and d3d9.
     wined3d, // PROTON_USE_WINED3D
     /// Disable `d3d11.dll`, for d3d11 games which can fall back to and run better with d3d9.
@@ -21,23 +32,159 @@ pub enum RuntimeOption {
     noesync, // PROTON_NO_ESYNC
     /// Do not use futex-based in-process synchronization primitives. (Automatically disabled on systems with no `FUTEX_WAIT_MULTIPLE` support.)
     nofsync, // PROTON_NO_FSYNC
+    /// Do not attempt to use XIM (X Input Method), which can crash some games.
+    noxim, // PROTON_NO_XIM
     /// Enable NVIDIA's NVAPI GPU support library.
     enablenvapi, // PROTON_ENABLE_NVAPI
+    /// Force Wine to enable the `LARGE_ADDRESS_AWARE` flag for all executables.
+    forcelgadd, // PROTON_FORCE_LARGE_ADDRESS_AWARE
+    /// Delay freeing some memory, to work around use-after-free bugs in games.
+    heapdelayfree, // PROTON_HEAP_DELAY_FREE
+    /// Create an `S:` drive which points to the Steam Library which contains the game.
+    gamedrive, // PROTON_SET_GAME_DRIVE
+    /// Use an OpenGL extension string which is shorter, for games which crash on long extension strings.
+    oldglstr, // PROTON_OLD_GL_STRING
+    /// Hide NVIDIA GPUs from the game, reporting them as AMD GPUs instead.
+    hidenvgpu, // PROTON_HIDE_NVIDIA_GPU
+    /// Disable support for memory write watches in ntdll, which can improve performance in some games.
+    nowritewatch, // PROTON_NO_WRITE_WATCH
+    /// Run the game with Wine's native Wayland driver instead of `XWayland`.
+    enablewayland, // PROTON_ENABLE_WAYLAND
+    /// Enable HDR output, requires `enablewayland` or a compositor supporting it.
+    enablehdr, // PROTON_ENABLE_HDR
+    /// Enable seccomp-bpf filter to emulate native syscalls, required for some DRM protections.
+    seccomp, // PROTON_USE_SECCOMP
+    /// Use the Xalia accessibility tool to play gamepad-unfriendly games with a gamepad, `0` disables it.
+    xalia, // PROTON_USE_XALIA
+}
+
+impl RuntimeOption {
+    /// Every runtime option, in the order they are listed in
+    pub const ALL: [RuntimeOption; 23] = [
+        RuntimeOption::log,
+        RuntimeOption::logdir,
+        RuntimeOption::dumpdebugcommands,
+        RuntimeOption::debugdir,
+        RuntimeOption::crashreportdir,
+        RuntimeOption::waitattach,
+        RuntimeOption::wined3d,
+        RuntimeOption::nod3d11,
+        RuntimeOption::nod3d10,
+        RuntimeOption::noesync,
+        RuntimeOption::nofsync,
+        RuntimeOption::noxim,
+        RuntimeOption::enablenvapi,
+        RuntimeOption::forcelgadd,
+        RuntimeOption::heapdelayfree,
+        RuntimeOption::gamedrive,
+        RuntimeOption::oldglstr,
+        RuntimeOption::hidenvgpu,
+        RuntimeOption::nowritewatch,
+        RuntimeOption::enablewayland,
+        RuntimeOption::enablehdr,
+        RuntimeOption::seccomp,
+        RuntimeOption::xalia,
+    ];
+
+    #[must_use]
+    /// Returns the name the option is given by, as in Steam's compat config
+    pub fn name(self) -> &'static str {
+        match self {
+            RuntimeOption::log => "log",
+            RuntimeOption::logdir => "logdir",
+            RuntimeOption::dumpdebugcommands => "dumpdebugcommands",
+            RuntimeOption::debugdir => "debugdir",
+            RuntimeOption::crashreportdir => "crashreportdir",
+            RuntimeOption::waitattach => "waitattach",
+            RuntimeOption::wined3d => "wined3d",
+            RuntimeOption::nod3d11 => "nod3d11",
+            RuntimeOption::nod3d10 => "nod3d10",
+            RuntimeOption::noesync => "noesync",
+            RuntimeOption::nofsync => "nofsync",
+            RuntimeOption::noxim => "noxim",
+            RuntimeOption::enablenvapi => "enablenvapi",
+            RuntimeOption::forcelgadd => "forcelgadd",
+            RuntimeOption::heapdelayfree => "heapdelayfree",
+            RuntimeOption::gamedrive => "gamedrive",
+            RuntimeOption::oldglstr => "oldglstr",
+            RuntimeOption::hidenvgpu => "hidenvgpu",
+            RuntimeOption::nowritewatch => "nowritewatch",
+            RuntimeOption::enablewayland => "enablewayland",
+            RuntimeOption::enablehdr => "enablehdr",
+            RuntimeOption::seccomp => "seccomp",
+            RuntimeOption::xalia => "xalia",
+        }
+    }
+
+    #[must_use]
+    /// Returns what the option does
+    pub fn description(self) -> &'static str {
+        match self {
+            RuntimeOption::log => "Dump a useful debug log, a value such as `+seh,+loaddll` sets the WINEDEBUG channels",
+            RuntimeOption::logdir => "Output log files into the directory given, instead of the home directory",
+            RuntimeOption::dumpdebugcommands => "Write useful debug scripts for the game into $PROTON_DEBUG_DIR/proton_$USER/",
+            RuntimeOption::debugdir => "Root directory for the debug scripts, /tmp by default",
+            RuntimeOption::crashreportdir => "Write crash logs into the directory given",
+            RuntimeOption::waitattach => "Wait for a debugger to attach to the game process before running it",
+            RuntimeOption::wined3d => "Use OpenGL-based wined3d instead of Vulkan-based DXVK for d3d11, d3d10, and d3d9",
+            RuntimeOption::nod3d11 => "Disable d3d11.dll, for d3d11 games which can fall back to and run better with d3d9",
+            RuntimeOption::nod3d10 => "Disable d3d10.dll and dxgi.dll, for d3d10 games which can fall back to and run better with d3d9",
+            RuntimeOption::noesync => "Do not use eventfd-based in-process synchronization primitives",
+            RuntimeOption::nofsync => "Do not use futex-based in-process synchronization primitives",
+            RuntimeOption::noxim => "Do not attempt to use XIM (X Input Method), which can crash some games",
+            RuntimeOption::enablenvapi => "Enable NVIDIA's NVAPI GPU support library",
+            RuntimeOption::forcelgadd => "Force Wine to enable the LARGE_ADDRESS_AWARE flag for all executables",
+            RuntimeOption::heapdelayfree => "Delay freeing some memory, to work around use-after-free bugs in games",
+            RuntimeOption::gamedrive => "Create an S: drive which points to the Steam Library which contains the game",
+            RuntimeOption::oldglstr => "Use a shorter OpenGL extension string, for games which crash on long extension strings",
+            RuntimeOption::hidenvgpu => "Hide NVIDIA GPUs from the game, reporting them as AMD GPUs instead",
+            RuntimeOption::nowritewatch => "Disable support for memory write watches in ntdll, which can improve performance",
+            RuntimeOption::enablewayland => "Run the game with Wine's native Wayland driver instead of XWayland",
+            RuntimeOption::enablehdr => "Enable HDR output",
+            RuntimeOption::seccomp => "Enable seccomp-bpf filter to emulate native syscalls, required for some DRM protections",
+            RuntimeOption::xalia => "Use Xalia to play gamepad-unfriendly games with a gamepad, `0` disables it",
+        }
+    }
+
+    #[must_use]
+    /// Returns true if the option must be given a value, such as a directory
+    pub fn requires_value(self) -> bool {
+        matches!(
+            self,
+            RuntimeOption::logdir | RuntimeOption::debugdir | RuntimeOption::crashreportdir
+        )
+    }
 }
 
 impl Display for RuntimeOption {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let opt = match self {
             RuntimeOption::log => "PROTON_LOG",
+            RuntimeOption::logdir => "PROTON_LOG_DIR",
+            RuntimeOption::dumpdebugcommands => "PROTON_DUMP_DEBUG_COMMANDS",
+            RuntimeOption::debugdir => "PROTON_DEBUG_DIR",
+            RuntimeOption::crashreportdir => "PROTON_CRASH_REPORT_DIR",
+            RuntimeOption::waitattach => "PROTON_WAIT_ATTACH",
             RuntimeOption::wined3d => "PROTON_USE_WINED3D",
             RuntimeOption::nod3d11 => "PROTON_NO_D3D11",
             RuntimeOption::nod3d10 => "PROTON_NO_D3D10",
             RuntimeOption::noesync => "PROTON_NO_ESYNC",
             RuntimeOption::nofsync => "PROTON_NO_FSYNC",
+            RuntimeOption::noxim => "PROTON_NO_XIM",
             RuntimeOption::enablenvapi => "PROTON_ENABLE_NVAPI",
+            RuntimeOption::forcelgadd => "PROTON_FORCE_LARGE_ADDRESS_AWARE",
+            RuntimeOption::heapdelayfree => "PROTON_HEAP_DELAY_FREE",
+            RuntimeOption::gamedrive => "PROTON_SET_GAME_DRIVE",
+            RuntimeOption::oldglstr => "PROTON_OLD_GL_STRING",
+            RuntimeOption::hidenvgpu => "PROTON_HIDE_NVIDIA_GPU",
+            RuntimeOption::nowritewatch => "PROTON_NO_WRITE_WATCH",
+            RuntimeOption::enablewayland => "PROTON_ENABLE_WAYLAND",
+            RuntimeOption::enablehdr => "PROTON_ENABLE_HDR",
+            RuntimeOption::seccomp => "PROTON_USE_SECCOMP",
+            RuntimeOption::xalia => "PROTON_USE_XALIA",
         };
 
-        write!(f, "{}", opt)
+        write!(f, "{opt}")
     }
 }
 
@@ -46,14 +193,95 @@ impl FromStr for RuntimeOption {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
-            "log" => Ok(Self::log),
-            "wined3d" => Ok(Self::wined3d),
-            "nod3d11" => Ok(Self::nod3d11),
-            "nod3d10" => Ok(Self::nod3d10),
-            "noesync" => Ok(Self::noesync),
-            "nofsync" => Ok(Self::nofsync),
             "enablenvapi" | "nvapi" => Ok(Self::enablenvapi),
-            _ => throw!(Kind::ParseRuntimeOpt, "{} is not a runtime option", s),
+            "wayland" => Ok(Self::enablewayland),
+            "hdr" => Ok(Self::enablehdr),
+            _ => match RuntimeOption::ALL.iter().find(|opt| opt.name() == s) {
+                Some(opt) => Ok(*opt),
+                None => throw!(Kind::ParseRuntimeOpt, "{} is not a runtime option", s),
+            },
+        }
+    }
+}
+
+impl TryFrom<String> for RuntimeOption {
+    type Error = crate::Error;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+/// Runtime option and the value it is set to, given as `name` or `name=value`
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, serde::Deserialize)]
+#[serde(try_from = "String")]
+pub struct RuntimeSetting {
+    option: RuntimeOption,
+    value: String,
+}
+
+impl RuntimeSetting {
+    #[must_use]
+    /// Creates a setting which enables `option`
+    pub fn new(option: RuntimeOption) -> RuntimeSetting {
+        RuntimeSetting {
+            option,
+            value: "1".to_string(),
+        }
+    }
+
+    #[must_use]
+    #[inline]
+    /// Returns the option which is set
+    pub fn option(&self) -> RuntimeOption {
+        self.option
+    }
+
+    #[must_use]
+    #[inline]
+    /// Returns the value the option is set to
+    pub fn value(&self) -> String {
+        self.value.clone()
+    }
+}
+
+impl Display for RuntimeSetting {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}={}", self.option, self.value)
+    }
+}
+
+impl FromStr for RuntimeSetting {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, value): (&str, Option<&str>) = match s.split_once('=') {
+            Some((name, value)) => (name, Some(value)),
+            None => (s, None),
+        };
+
+        let option: RuntimeOption = name.parse()?;
+
+        match value {
+            Some(value) => pass!(RuntimeSetting {
+                option,
+                value: value.to_string(),
+            }),
+            None if option.requires_value() => throw!(
+                Kind::ParseRuntimeOpt,
+                "{} requires a value, such as {}=VALUE",
+                name,
+                name
+            ),
+            None => pass!(RuntimeSetting::new(option)),
         }
     }
 }
+
+impl TryFrom<String> for RuntimeSetting {
+    type Error = crate::Error;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
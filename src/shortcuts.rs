@@ -0,0 +1,472 @@
+use crate::error::{Error, Kind};
+use crate::hash::crc32;
+use crate::{pass, throw};
+use std::path::{Path, PathBuf};
+
+/// Type bytes of the binary VDF format `shortcuts.vdf` is kept in
+const MAP: u8 = 0x00;
+const STRING: u8 = 0x01;
+const INT: u8 = 0x02;
+const LONG: u8 = 0x07;
+const END: u8 = 0x08;
+
+/// Deepest maps are nested in `shortcuts.vdf`, Steam itself nests them three deep
+const MAX_DEPTH: usize = 32;
+
+/// Value in a binary VDF file
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Value {
+    Map(Vec<(String, Value)>),
+    String(String),
+    Int(u32),
+    Long(u64),
+}
+
+/// Returns the App ID Steam gives a non-Steam shortcut, from its `Exe` as written, quotes and
+/// all, and its name
+#[must_use]
+pub fn appid(exe: &str, name: &str) -> u32 {
+    crc32(format!("{exe}{name}").as_bytes()) | 0x8000_0000
+}
+
+/// Non-Steam game in Steam's library
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Shortcut {
+    appid: u32,
+    name: String,
+    exe: String,
+    start_dir: String,
+    launch_options: String,
+    icon: String,
+}
+
+impl Shortcut {
+    #[must_use]
+    /// Creates a shortcut named `name` which runs `exe` in `start_dir` with `launch_options`.
+    /// Steam expects `exe` and `start_dir` in quotes
+    pub fn new(name: &str, exe: &str, start_dir: &str, launch_options: &str) -> Shortcut {
+        Shortcut {
+            appid: appid(exe, name),
+            name: name.to_string(),
+            exe: exe.to_string(),
+            start_dir: start_dir.to_string(),
+            launch_options: launch_options.to_string(),
+            icon: String::new(),
+        }
+    }
+
+    #[must_use]
+    /// Sets the image the library shows the shortcut with
+    pub fn with_icon(mut self, icon: Option<&Path>) -> Shortcut {
+        self.icon = icon
+            .map(|icon| icon.to_string_lossy().to_string())
+            .unwrap_or_default();
+        self
+    }
+
+    #[must_use]
+    #[inline]
+    /// Returns the App ID of the shortcut
+    pub fn appid(&self) -> u32 {
+        self.appid
+    }
+
+    #[must_use]
+    #[inline]
+    /// Returns the name of the shortcut in the library
+    pub fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    #[must_use]
+    #[inline]
+    /// Returns the program the shortcut runs, as Steam keeps it, usually in quotes
+    pub fn exe(&self) -> String {
+        self.exe.clone()
+    }
+
+    #[must_use]
+    #[inline]
+    /// Returns the directory the shortcut runs in, as Steam keeps it, usually in quotes
+    pub fn start_dir(&self) -> String {
+        self.start_dir.clone()
+    }
+
+    #[must_use]
+    #[inline]
+    /// Returns the launch options of the shortcut
+    pub fn launch_options(&self) -> String {
+        self.launch_options.clone()
+    }
+
+    #[must_use]
+    #[inline]
+    /// Returns the image the library shows the shortcut with, empty when it has none
+    pub fn icon(&self) -> String {
+        self.icon.clone()
+    }
+
+    /// Reads a shortcut from its entry in `shortcuts.vdf`
+    fn from_entry(entry: &[(String, Value)]) -> Shortcut {
+        let string = |key: &str| match field(entry, key) {
+            Some(Value::String(s)) => s.clone(),
+            _ => String::new(),
+        };
+
+        let exe: String = string("Exe");
+        let name: String = string("AppName");
+
+        Shortcut {
+            appid: match field(entry, "appid") {
+                Some(Value::Int(appid)) => *appid,
+                _ => appid(&exe, &name),
+            },
+            start_dir: string("StartDir"),
+            launch_options: string("LaunchOptions"),
+            icon: string("icon"),
+            exe,
+            name,
+        }
+    }
+}
+
+/// Removes the quotes Steam puts around paths
+#[must_use]
+pub fn unquote(s: &str) -> String {
+    s.strip_prefix('"')
+        .and_then(|s| s.strip_suffix('"'))
+        .unwrap_or(s)
+        .to_string()
+}
+
+/// Finds `key` in `entry`, which Steam has written in different cases over time
+fn field<'a>(entry: &'a [(String, Value)], key: &str) -> Option<&'a Value> {
+    entry
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case(key))
+        .map(|(_, v)| v)
+}
+
+/// Sets `key` in `entry` to `value`, keeping its place if it is already there
+fn set_field(entry: &mut Vec<(String, Value)>, key: &str, value: Value) {
+    match entry.iter_mut().find(|(k, _)| k.eq_ignore_ascii_case(key)) {
+        Some((_, v)) => *v = value,
+        None => entry.insert(entry.len(), (key.to_string(), value)),
+    }
+}
+
+/// Steam's `shortcuts.vdf` of a user, the non-Steam games in their library
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Shortcuts {
+    path: PathBuf,
+    entries: Vec<Vec<(String, Value)>>,
+}
+
+impl Shortcuts {
+    /// Finds the `shortcuts.vdf` of `user` in Steam's `userdata`, or of the only user there is
+    ///
+    /// # Errors
+    ///
+    /// Will fail if `user` is not a Steam user, or none is given and there is not exactly one user
+    pub fn find(steam: &Path, user: Option<&str>) -> Result<PathBuf, Error> {
+        let userdata: PathBuf = steam.join("userdata");
+
+        let user: String = match user {
+            Some(user) if userdata.join(user).is_dir() => user.to_string(),
+            Some(user) => throw!(
+                Kind::Shortcuts,
+                "no Steam user '{}' in '{}'",
+                user,
+                userdata.display()
+            ),
+            None => {
+                // `0` holds settings for when no one is logged in
+                let mut users: Vec<String> = match userdata.read_dir() {
+                    Ok(rd) => rd
+                        .flatten()
+                        .map(|entry| entry.file_name().to_string_lossy().to_string())
+                        .filter(|name| name != "0" && name.chars().all(|c| c.is_ascii_digit()))
+                        .collect(),
+                    Err(e) => throw!(Kind::Shortcuts, "'{}': {}", userdata.display(), e),
+                };
+                users.sort();
+
+                match users.as_slice() {
+                    [user] => user.clone(),
+                    [] => throw!(
+                        Kind::Shortcuts,
+                        "no Steam users in '{}'",
+                        userdata.display()
+                    ),
+                    _ => throw!(
+                        Kind::Shortcuts,
+                        "several Steam users, give one with --user: {}",
+                        users.join(", ")
+                    ),
+                }
+            }
+        };
+
+        pass!(userdata.join(user).join("config/shortcuts.vdf"))
+    }
+
+    /// Opens the `shortcuts.vdf` at `path`, empty if it does not exist yet
+    ///
+    /// # Errors
+    ///
+    /// Will fail if the file can not be read, or is not a binary VDF file of shortcuts
+    pub fn open(path: &Path) -> Result<Shortcuts, Error> {
+        let mut shortcuts: Shortcuts = Shortcuts {
+            path: path.to_path_buf(),
+            entries: Vec::new(),
+        };
+
+        if !path.exists() {
+            return pass!(shortcuts);
+        }
+
+        let bytes: Vec<u8> = match std::fs::read(path) {
+            Ok(bytes) => bytes,
+            Err(e) => throw!(Kind::Shortcuts, "'{}': {}", path.display(), e),
+        };
+
+        let root: Vec<(String, Value)> = match read_map(&bytes, &mut 0, 0) {
+            Some(root) => root,
+            None => throw!(
+                Kind::Shortcuts,
+                "'{}': not a binary VDF file",
+                path.display()
+            ),
+        };
+
+        if let Some(Value::Map(entries)) = field(&root, "shortcuts") {
+            for (_, entry) in entries {
+                if let Value::Map(entry) = entry {
+                    shortcuts
+                        .entries
+                        .insert(shortcuts.entries.len(), entry.clone());
+                }
+            }
+        }
+
+        pass!(shortcuts)
+    }
+
+    #[must_use]
+    #[inline]
+    /// Returns the location of the file
+    pub fn path(&self) -> PathBuf {
+        self.path.clone()
+    }
+
+    #[must_use]
+    /// Returns every shortcut, in the order Steam keeps them
+    pub fn list(&self) -> Vec<Shortcut> {
+        self.entries
+            .iter()
+            .map(|entry| Shortcut::from_entry(entry))
+            .collect()
+    }
+
+    /// Adds `shortcut`, or updates the shortcut with the same App ID or name, keeping the rest
+    /// of its settings such as its tags. Returns true if a shortcut was updated
+    pub fn set(&mut self, shortcut: &Shortcut) -> bool {
+        let existing: Option<usize> = self.entries.iter().position(|entry| {
+            let current: Shortcut = Shortcut::from_entry(entry);
+            current.appid == shortcut.appid || current.name == shortcut.name
+        });
+
+        let index: usize = existing.unwrap_or_else(|| {
+            self.entries.insert(self.entries.len(), new_entry());
+            self.entries.len() - 1
+        });
+        let entry: &mut Vec<(String, Value)> = &mut self.entries[index];
+
+        set_field(entry, "appid", Value::Int(shortcut.appid));
+        set_field(entry, "AppName", Value::String(shortcut.name.clone()));
+        set_field(entry, "Exe", Value::String(shortcut.exe.clone()));
+        set_field(entry, "StartDir", Value::String(shortcut.start_dir.clone()));
+        set_field(
+            entry,
+            "LaunchOptions",
+            Value::String(shortcut.launch_options.clone()),
+        );
+
+        // an icon which could not be found keeps the one it had
+        if !shortcut.icon.is_empty() {
+            set_field(entry, "icon", Value::String(shortcut.icon.clone()));
+        }
+
+        existing.is_some()
+    }
+
+    /// Writes the shortcuts back, keeping the file as it was in `shortcuts.vdf.bak`
+    ///
+    /// # Errors
+    ///
+    /// Will fail if the file can not be written
+    pub fn save(&self) -> Result<(), Error> {
+        let shortcuts: Vec<(String, Value)> = self
+            .entries
+            .iter()
+            .enumerate()
+            .map(|(i, entry)| (i.to_string(), Value::Map(entry.clone())))
+            .collect();
+
+        let mut bytes: Vec<u8> = Vec::new();
+        write_map(
+            &mut bytes,
+            &[("shortcuts".to_string(), Value::Map(shortcuts))],
+        );
+
+        let result: std::io::Result<()> = (|| {
+            if let Some(dir) = self.path.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+
+            if self.path.exists() {
+                std::fs::copy(&self.path, self.path.with_extension("vdf.bak"))?;
+            }
+
+            // written beside it first, so Steam never sees half a file
+            let part: PathBuf = self.path.with_extension("vdf.part");
+            std::fs::write(&part, &bytes)?;
+            std::fs::rename(&part, &self.path)
+        })();
+
+        if let Err(e) = result {
+            throw!(Kind::Shortcuts, "'{}': {}", self.path.display(), e);
+        }
+
+        pass!()
+    }
+}
+
+/// Settings Steam gives a shortcut added from its library
+fn new_entry() -> Vec<(String, Value)> {
+    let string = |key: &str| (key.to_string(), Value::String(String::new()));
+    let int = |key: &str, value: u32| (key.to_string(), Value::Int(value));
+
+    vec![
+        int("appid", 0),
+        string("AppName"),
+        string("Exe"),
+        string("StartDir"),
+        string("icon"),
+        string("ShortcutPath"),
+        string("LaunchOptions"),
+        int("IsHidden", 0),
+        int("AllowDesktopConfig", 1),
+        int("AllowOverlay", 1),
+        int("OpenVR", 0),
+        int("Devkit", 0),
+        string("DevkitGameID"),
+        int("DevkitOverrideAppID", 0),
+        int("LastPlayTime", 0),
+        string("FlatpakAppID"),
+        ("tags".to_string(), Value::Map(Vec::new())),
+    ]
+}
+
+/// Reads a NUL terminated string at `pos`
+fn read_string(bytes: &[u8], pos: &mut usize) -> Option<String> {
+    let rest: &[u8] = bytes.get(*pos..)?;
+    let end: usize = rest.iter().position(|b| *b == 0)?;
+    *pos += end + 1;
+    Some(String::from_utf8_lossy(&rest[..end]).to_string())
+}
+
+/// Reads `N` bytes at `pos`
+fn read_bytes<const N: usize>(bytes: &[u8], pos: &mut usize) -> Option<[u8; N]> {
+    let read: [u8; N] = bytes.get(*pos..*pos + N)?.try_into().ok()?;
+    *pos += N;
+    Some(read)
+}
+
+/// Reads the entries of a map at `pos`, up to its end, or the end of the file for the root.
+/// `depth` is the number of maps it is nested in, a map nested past `MAX_DEPTH` is not read
+fn read_map(bytes: &[u8], pos: &mut usize, depth: usize) -> Option<Vec<(String, Value)>> {
+    if depth > MAX_DEPTH {
+        return None;
+    }
+
+    let mut map: Vec<(String, Value)> = Vec::new();
+
+    while let Some(kind) = bytes.get(*pos).copied() {
+        *pos += 1;
+
+        if kind == END {
+            return Some(map);
+        }
+
+        let key: String = read_string(bytes, pos)?;
+        let value: Value = match kind {
+            MAP => Value::Map(read_map(bytes, pos, depth + 1)?),
+            STRING => Value::String(read_string(bytes, pos)?),
+            INT => Value::Int(u32::from_le_bytes(read_bytes(bytes, pos)?)),
+            LONG => Value::Long(u64::from_le_bytes(read_bytes(bytes, pos)?)),
+            _ => return None,
+        };
+
+        map.insert(map.len(), (key, value));
+    }
+
+    Some(map)
+}
+
+/// Writes the entries of a map, and its end
+fn write_map(bytes: &mut Vec<u8>, map: &[(String, Value)]) {
+    for (key, value) in map {
+        let kind: u8 = match value {
+            Value::Map(_) => MAP,
+            Value::String(_) => STRING,
+            Value::Int(_) => INT,
+            Value::Long(_) => LONG,
+        };
+
+        bytes.insert(bytes.len(), kind);
+        bytes.extend_from_slice(key.as_bytes());
+        bytes.insert(bytes.len(), 0);
+
+        match value {
+            Value::Map(map) => write_map(bytes, map),
+            Value::String(s) => {
+                bytes.extend_from_slice(s.as_bytes());
+                bytes.insert(bytes.len(), 0);
+            }
+            Value::Int(i) => bytes.extend_from_slice(&i.to_le_bytes()),
+            Value::Long(l) => bytes.extend_from_slice(&l.to_le_bytes()),
+        }
+    }
+
+    bytes.insert(bytes.len(), END);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{read_map, write_map, Value, MAP};
+
+    #[test]
+    fn reads_written_maps() {
+        let map: Vec<(String, Value)> = vec![(
+            "shortcuts".to_string(),
+            Value::Map(vec![(
+                "AppName".to_string(),
+                Value::String("Game".to_string()),
+            )]),
+        )];
+
+        let mut bytes: Vec<u8> = Vec::new();
+        write_map(&mut bytes, &map);
+
+        assert_eq!(read_map(&bytes, &mut 0, 0), Some(map));
+    }
+
+    #[test]
+    fn rejects_deeply_nested_maps() {
+        // a map named `a` in every map, far deeper than the stack allows
+        let bytes: Vec<u8> = [MAP, b'a', 0].repeat(1_000_000);
+
+        assert_eq!(read_map(&bytes, &mut 0, 0), None);
+    }
+}
@@ -0,0 +1,68 @@
+use std::{
+    fmt::{Display, Formatter},
+    str::FromStr,
+};
+
+use crate::{error::Kind, throw};
+
+/// Wine tools which can be run in a prefix in place of a program
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Tool {
+    /// Wine's configuration, `winecfg`
+    Winecfg,
+    /// Registry editor, `regedit`
+    Regedit,
+    /// Updates, restarts or shuts down the prefix, `wineboot`
+    Wineboot,
+    /// Windows command prompt, `cmd`
+    Cmd,
+    /// Task manager, `taskmgr`
+    Taskmgr,
+}
+
+impl Tool {
+    /// Every tool, in the order they are listed in
+    pub const ALL: [Tool; 5] = [
+        Tool::Winecfg,
+        Tool::Regedit,
+        Tool::Wineboot,
+        Tool::Cmd,
+        Tool::Taskmgr,
+    ];
+
+    #[must_use]
+    /// Returns the program of the tool, as Wine finds it in the prefix's `system32`
+    pub fn exe(self) -> &'static str {
+        match self {
+            Tool::Winecfg => "winecfg",
+            Tool::Regedit => "regedit",
+            Tool::Wineboot => "wineboot",
+            Tool::Cmd => "cmd",
+            Tool::Taskmgr => "taskmgr",
+        }
+    }
+}
+
+impl Display for Tool {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.exe())
+    }
+}
+
+impl FromStr for Tool {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name: String = s.to_lowercase();
+        let name: &str = name.strip_suffix(".exe").unwrap_or(&name);
+
+        match Tool::ALL.iter().find(|tool| tool.exe() == name) {
+            Some(tool) => Ok(*tool),
+            None => throw!(
+                Kind::ArgumentInvalid,
+                "--tool '{}', expected one of winecfg, regedit, wineboot, cmd or taskmgr",
+                s
+            ),
+        }
+    }
+}
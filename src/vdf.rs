@@ -0,0 +1,158 @@
+use crate::error::{Error, Kind};
+use crate::{pass, throw};
+use std::iter::Peekable;
+use std::path::Path;
+use std::str::Chars;
+
+/// Node of a text VDF (`KeyValues`) file, as used by Steam for its library and tool manifests
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Vdf {
+    /// String value
+    Str(String),
+    /// Table of key value pairs, in the order of the file
+    Table(Vec<(String, Vdf)>),
+}
+
+impl Vdf {
+    /// Opens and parses the VDF file at `path`
+    ///
+    /// # Errors
+    ///
+    /// Will fail if the file can not be read or parsed
+    pub fn open(path: &Path) -> Result<Vdf, Error> {
+        match std::fs::read_to_string(path) {
+            Ok(s) => Vdf::parse(&s),
+            Err(e) => throw!(Kind::VdfRead, "'{}': {}", path.display(), e),
+        }
+    }
+
+    /// Parses a VDF document into a table of its top level keys
+    ///
+    /// # Errors
+    ///
+    /// Will fail on unbalanced braces, unterminated strings or keys without values
+    pub fn parse(s: &str) -> Result<Vdf, Error> {
+        let mut chars: Peekable<Chars> = s.chars().peekable();
+        let table: Vec<(String, Vdf)> = Vdf::parse_table(&mut chars, false)?;
+        pass!(Vdf::Table(table))
+    }
+
+    #[must_use]
+    /// Returns the value of `key` in a table, keys are not case sensitive
+    pub fn get(&self, key: &str) -> Option<&Vdf> {
+        self.entries()
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))
+            .map(|(_, v)| v)
+    }
+
+    #[must_use]
+    /// Returns the string value, if this is not a table
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Vdf::Str(s) => Some(s),
+            Vdf::Table(_) => None,
+        }
+    }
+
+    #[must_use]
+    /// Returns the key value pairs of a table, a string has none
+    pub fn entries(&self) -> &[(String, Vdf)] {
+        match self {
+            Vdf::Str(_) => &[],
+            Vdf::Table(t) => t,
+        }
+    }
+
+    fn parse_table(chars: &mut Peekable<Chars>, nested: bool) -> Result<Vec<(String, Vdf)>, Error> {
+        let mut table: Vec<(String, Vdf)> = Vec::new();
+
+        loop {
+            let key: String = match Vdf::next_token(chars)? {
+                Some(Token::Str(key)) => key,
+                Some(Token::Close) if nested => return pass!(table),
+                None if !nested => return pass!(table),
+                Some(Token::Close) => throw!(Kind::VdfParse, "unexpected '}}'"),
+                Some(Token::Open) => throw!(Kind::VdfParse, "unexpected '{{'"),
+                None => throw!(Kind::VdfParse, "missing '}}'"),
+            };
+
+            let value: Vdf = match Vdf::next_token(chars)? {
+                Some(Token::Str(value)) => Vdf::Str(value),
+                Some(Token::Open) => Vdf::Table(Vdf::parse_table(chars, true)?),
+                _ => throw!(Kind::VdfParse, "missing value for '{}'", key),
+            };
+
+            table.insert(table.len(), (key, value));
+        }
+    }
+
+    fn next_token(chars: &mut Peekable<Chars>) -> Result<Option<Token>, Error> {
+        loop {
+            match chars.peek().copied() {
+                Some(c) if c.is_whitespace() => {
+                    chars.next();
+                }
+                Some('/') if chars.clone().nth(1) == Some('/') => {
+                    for c in chars.by_ref() {
+                        if c == '\n' {
+                            break;
+                        }
+                    }
+                }
+                // platform conditionals such as `[$WIN32]` are ignored
+                Some('[') => {
+                    for c in chars.by_ref() {
+                        if c == ']' {
+                            break;
+                        }
+                    }
+                }
+                _ => break,
+            }
+        }
+
+        let token: Token = match chars.next() {
+            None => return pass!(None),
+            Some('{') => Token::Open,
+            Some('}') => Token::Close,
+            Some('"') => {
+                let mut s: String = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some('n') => s.push('\n'),
+                            Some('t') => s.push('\t'),
+                            Some(c) => s.push(c),
+                            None => throw!(Kind::VdfParse, "unterminated string"),
+                        },
+                        Some(c) => s.push(c),
+                        None => throw!(Kind::VdfParse, "unterminated string"),
+                    }
+                }
+                Token::Str(s)
+            }
+            Some(c) => {
+                let mut s: String = c.to_string();
+                while let Some(c) = chars.peek() {
+                    if c.is_whitespace() || matches!(c, '"' | '{' | '}') {
+                        break;
+                    }
+                    s.push(*c);
+                    chars.next();
+                }
+                Token::Str(s)
+            }
+        };
+
+        pass!(Some(token))
+    }
+}
+
+/// Token of a VDF document
+enum Token {
+    Str(String),
+    Open,
+    Close,
+}
@@ -0,0 +1,72 @@
+use std::{
+    fmt::{Display, Formatter},
+    str::FromStr,
+};
+
+use crate::{error::Kind, throw};
+
+/// Verbs Proton and the Steam runtime are run with, as Steam passes them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Verb {
+    /// Runs the program, setting up the prefix first
+    Run,
+    /// Waits for the prefix to be idle, then runs the program the same as `Run`
+    WaitForExitAndRun,
+    /// Runs the program in the prefix, without setting it up
+    RunInPrefix,
+    /// Converts a Unix path into the path Windows programs see in the prefix
+    GetCompatPath,
+    /// Converts a Windows path in the prefix into its Unix path
+    GetNativePath,
+}
+
+impl Verb {
+    /// Every verb, in the order they are listed in
+    pub const ALL: [Verb; 5] = [
+        Verb::Run,
+        Verb::WaitForExitAndRun,
+        Verb::RunInPrefix,
+        Verb::GetCompatPath,
+        Verb::GetNativePath,
+    ];
+
+    #[must_use]
+    /// Returns the verb the way Proton takes it
+    pub fn name(self) -> &'static str {
+        match self {
+            Verb::Run => "run",
+            Verb::WaitForExitAndRun => "waitforexitandrun",
+            Verb::RunInPrefix => "runinprefix",
+            Verb::GetCompatPath => "getcompatpath",
+            Verb::GetNativePath => "getnativepath",
+        }
+    }
+
+    #[must_use]
+    /// Returns true if the verb converts a path, given in place of the program, and prints it
+    pub fn converts_path(self) -> bool {
+        matches!(self, Verb::GetCompatPath | Verb::GetNativePath)
+    }
+}
+
+impl Display for Verb {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Verb {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match Verb::ALL.iter().find(|verb| verb.name() == s) {
+            Some(verb) => Ok(*verb),
+            None => throw!(
+                Kind::ArgumentInvalid,
+                "--verb '{}', expected one of run, waitforexitandrun, runinprefix, getcompatpath or getnativepath",
+                s
+            ),
+        }
+    }
+}
@@ -1,52 +1,256 @@
 use crate::{pass, throw, Error, Kind};
 use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
+use std::cmp::Ordering;
 use std::ffi::OsStr;
 use std::fmt::{Display, Formatter};
 use std::hash::Hash;
 use std::path::Path;
 use std::str::FromStr;
 
-/// Version type to handle Proton Versions
+/// Flavour type to handle the different builds of Proton
 #[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Serialize, Deserialize, Hash)]
-pub enum Version {
-    /// Two number version
-    Mainline(u8, u8),
-    /// Experimental version
-    Experimental,
-    /// Custom version (will be replaced by Mainline if possible)
+pub enum Flavour {
+    /// Numbered release from Valve, such as `Proton 8.0`
+    Valve,
+    /// `GloriousEggroll` build, such as `GE-Proton9-20`
+    GE,
+    /// `TkG` build, such as `proton_tkg_8.12.r0`
+    TKG,
+    /// Custom build which does not follow any known naming
     Custom,
+    /// Valve's `Proton Hotfix`
+    Hotfix,
+    /// Valve's `Proton Next`
+    Next,
+    /// Valve's `Proton - Experimental`
+    Experimental,
+}
+
+impl Display for Flavour {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let flavour = match self {
+            Flavour::Valve => "Valve",
+            Flavour::GE => "GE",
+            Flavour::TKG => "TKG",
+            Flavour::Custom => "Custom",
+            Flavour::Hotfix => "Hotfix",
+            Flavour::Next => "Next",
+            Flavour::Experimental => "Experimental",
+        };
+
+        write!(f, "{flavour}")
+    }
+}
+
+impl Flavour {
+    #[must_use]
+    /// Returns true if the flavour is a rolling release without a version number
+    pub fn is_rolling(self) -> bool {
+        matches!(
+            self,
+            Flavour::Hotfix | Flavour::Next | Flavour::Experimental
+        )
+    }
+}
+
+/// Version type to handle Proton Versions
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize, Hash)]
+pub struct Version {
+    flavour: Flavour,
+    major: u16,
+    minor: u16,
+    patch: u16,
+    build: u16,
+}
+
+impl Ord for Version {
+    /// Rolling releases are newer than any numbered release, numbered releases
+    /// are compared by their numbers before their flavour
+    fn cmp(&self, other: &Self) -> Ordering {
+        let key = |v: &Version| {
+            (
+                v.flavour.is_rolling(),
+                v.major,
+                v.minor,
+                v.patch,
+                v.build,
+                v.flavour,
+            )
+        };
+
+        key(self).cmp(&key(other))
+    }
 }
 
-impl Default for Version {
-    fn default() -> Self {
-        Version::Mainline(7, 0)
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
     }
 }
 
 impl Version {
     #[must_use]
-    /// Creates a new `Version::Mainline` instance
-    pub fn new(major: u8, minor: u8) -> Version {
-        Version::Mainline(major, minor)
+    /// Creates a new Valve `Version` with two numbers
+    pub fn new(major: u16, minor: u16) -> Version {
+        Version::with_flavour(Flavour::Valve, major, minor, 0, 0)
     }
 
     #[must_use]
-    /// Converts path to custon proton version into Version enum
+    /// Creates a new `Version` of any flavour
+    pub fn with_flavour(
+        flavour: Flavour,
+        major: u16,
+        minor: u16,
+        patch: u16,
+        build: u16,
+    ) -> Version {
+        Version {
+            flavour,
+            major,
+            minor,
+            patch,
+            build,
+        }
+    }
+
+    #[must_use]
+    /// Converts path to custon proton version into Version
     pub fn from_custom(name: &Path) -> Version {
         let name_osstr: &OsStr = name.file_name().unwrap_or_else(|| OsStr::new("custom"));
         let name_str: Cow<str> = name_osstr.to_string_lossy();
-        let version_str: &str = name_str.split(' ').last().unwrap_or("custom");
-        version_str.parse().unwrap_or(Version::Custom)
+        name_str
+            .parse()
+            .unwrap_or_else(|_| Version::with_flavour(Flavour::Custom, 0, 0, 0, 0))
+    }
+
+    #[must_use]
+    #[inline]
+    /// Returns the flavour of the version
+    pub fn flavour(&self) -> Flavour {
+        self.flavour
+    }
+
+    #[must_use]
+    #[inline]
+    /// Returns the major version number
+    pub fn major(&self) -> u16 {
+        self.major
+    }
+
+    #[must_use]
+    #[inline]
+    /// Returns the minor version number
+    pub fn minor(&self) -> u16 {
+        self.minor
+    }
+
+    #[must_use]
+    #[inline]
+    /// Returns the patch version number
+    pub fn patch(&self) -> u16 {
+        self.patch
+    }
+
+    #[must_use]
+    #[inline]
+    /// Returns the build number
+    pub fn build(&self) -> u16 {
+        self.build
+    }
+
+    #[must_use]
+    /// Returns true if this version and `other` can be told apart as older and newer. Rolling
+    /// releases and custom builds have no number to compare
+    pub fn is_comparable(&self, other: Version) -> bool {
+        let numbered = |v: &Version| v.flavour != Flavour::Custom && !v.flavour.is_rolling();
+
+        numbered(self) && numbered(&other)
+    }
+
+    #[must_use]
+    /// Returns true if `other` is the same release, where one gives no patch or build, such as
+    /// `7.0` from the index and `7.0-5` from a prefix's `config_info`
+    pub fn is_same_release(&self, other: Version) -> bool {
+        let unnumbered = |v: &Version| v.patch == 0 && v.build == 0;
+
+        self.flavour == other.flavour
+            && self.major == other.major
+            && self.minor == other.minor
+            && (unnumbered(self) || unnumbered(&other))
+    }
+
+    /// Parses the numbers of a Valve version, which may only hold digits, `.` and `-`
+    fn parse_valve(s: &str) -> Option<Version> {
+        if s.chars()
+            .all(|c| c.is_ascii_digit() || c == '.' || c == '-')
+        {
+            Version::parse_numbers(Flavour::Valve, s)
+        } else {
+            None
+        }
+    }
+
+    /// Parses the numbers of a version, such as `8.0-5`, `9-20` or `8.12.r3.gabcdef`
+    fn parse_numbers(flavour: Flavour, s: &str) -> Option<Version> {
+        let (numbers, build) = match s.find(['-', 'r']) {
+            Some(i) => (s[..i].trim_end_matches('.'), Some(&s[i + 1..])),
+            None => (s, None),
+        };
+
+        let numbers: Vec<u16> = numbers
+            .split('.')
+            .map(str::parse)
+            .collect::<Result<Vec<u16>, _>>()
+            .ok()?;
+
+        let (major, minor, patch) = match numbers.as_slice() {
+            [major] => (*major, 0, 0),
+            [major, minor] => (*major, *minor, 0),
+            [major, minor, patch] => (*major, *minor, *patch),
+            _ => return None,
+        };
+
+        let build: u16 = match build {
+            Some(build) => {
+                let build: &str = build.trim_start_matches('r');
+                let end: usize = build
+                    .find(|c: char| !c.is_ascii_digit())
+                    .unwrap_or(build.len());
+                build[..end].parse().ok()?
+            }
+            None => 0,
+        };
+
+        Some(Version::with_flavour(flavour, major, minor, patch, build))
     }
 }
 
 impl Display for Version {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Version::Mainline(mj, mn) => write!(f, "{}.{}", mj, mn),
-            Version::Experimental => write!(f, "Experimental"),
-            Version::Custom => write!(f, "Custom"),
+        let mut numbers: String = format!("{}.{}", self.major, self.minor);
+
+        if self.patch != 0 {
+            numbers = format!("{}.{}", numbers, self.patch);
+        }
+
+        let build: String = if self.build == 0 {
+            String::new()
+        } else {
+            format!("-{}", self.build)
+        };
+
+        match self.flavour {
+            Flavour::Valve => write!(f, "{numbers}{build}"),
+            Flavour::GE if self.minor == 0 && self.patch == 0 => {
+                write!(f, "GE-Proton{}{}", self.major, build)
+            }
+            Flavour::GE => write!(f, "GE-Proton{numbers}{build}"),
+            Flavour::TKG => write!(f, "TKG-{numbers}{build}"),
+            Flavour::Custom => write!(f, "Custom"),
+            Flavour::Hotfix => write!(f, "Hotfix"),
+            Flavour::Next => write!(f, "Next"),
+            Flavour::Experimental => write!(f, "Experimental"),
         }
     }
 }
@@ -54,14 +258,102 @@ impl Display for Version {
 impl FromStr for Version {
     type Err = Error;
 
+    /// Parses names of Proton versions and their directories, such as `7.0`, `Proton 8.0-5`,
+    /// `Proton - Experimental`, `Proton Hotfix`, `GE-Proton9-20`, `Proton-6.21-GE-2` and `proton_tkg_8.12.r0`
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if s.to_ascii_lowercase() == "experimental" {
-            return pass!(Version::Experimental);
+        let lower: String = s.trim().to_ascii_lowercase();
+
+        let words: Vec<&str> = lower
+            .split([' ', '-', '_'])
+            .filter(|w| !w.is_empty() && *w != "proton")
+            .collect();
+
+        match words.as_slice() {
+            ["experimental"] => {
+                return pass!(Version::with_flavour(Flavour::Experimental, 0, 0, 0, 0))
+            }
+            ["hotfix"] => return pass!(Version::with_flavour(Flavour::Hotfix, 0, 0, 0, 0)),
+            ["next"] => return pass!(Version::with_flavour(Flavour::Next, 0, 0, 0, 0)),
+            _ => {}
+        }
+
+        let parsed: Option<Version> = if let Some(rest) = lower.strip_prefix("ge-proton") {
+            Version::parse_numbers(Flavour::GE, rest)
+        } else if let Some((numbers, build)) = lower.split_once("-ge-") {
+            let numbers: &str = numbers.trim_start_matches("proton").trim_start_matches('-');
+            Version::parse_numbers(Flavour::GE, &format!("{numbers}-{build}"))
+        } else if let Some(i) = lower.find("tkg") {
+            let rest: &str = lower[i + 3..].trim_start_matches(['-', '_']);
+            Version::parse_numbers(Flavour::TKG, rest)
+        } else if let Some(rest) = lower.strip_prefix("proton") {
+            // other words may only follow the version of a named Proton, such as `Proton 5.0 Beta`
+            let rest: &str = rest.trim_start_matches([' ', '-', '_']);
+            let rest: &str = rest.split_whitespace().next().unwrap_or_default();
+            Version::parse_valve(rest)
+        } else {
+            // a bare version is the whole name, so games such as `7 Days to Die` are not taken for Proton
+            Version::parse_valve(&lower)
+        };
+
+        match parsed {
+            Some(version) => pass!(version),
+            None => throw!(Kind::VersionParse, "'{}'", s),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Flavour, Version};
+
+    #[test]
+    fn parses_valve_versions() {
+        assert_eq!("8.0".parse::<Version>().ok(), Some(Version::new(8, 0)));
+        assert_eq!(
+            "8.0-5".parse::<Version>().ok(),
+            Some(Version::with_flavour(Flavour::Valve, 8, 0, 0, 5))
+        );
+        assert_eq!(
+            "Proton 7.0".parse::<Version>().ok(),
+            Some(Version::new(7, 0))
+        );
+        assert_eq!(
+            "Proton 5.0 Beta".parse::<Version>().ok(),
+            Some(Version::new(5, 0))
+        );
+    }
+
+    #[test]
+    fn rejects_game_directories() {
+        for name in [
+            "60 Seconds!",
+            "7 Days to Die",
+            "911 Operator",
+            "1000-1 Nights",
+            "8-Bit Armies",
+            "2064 Read Only Memories",
+        ] {
+            assert!(name.parse::<Version>().is_err(), "{name}");
         }
+    }
+
+    #[test]
+    fn rolling_releases_are_not_compared() {
+        let numbered: Version = Version::new(9, 0);
 
-        match s.split('.').collect::<Vec<&str>>().as_slice() {
-            [maj, min] => pass!(Version::new(maj.parse()?, min.parse()?)),
-            _ => throw!(Kind::VersionParse, "'{}'", s),
+        for flavour in [
+            Flavour::Experimental,
+            Flavour::Hotfix,
+            Flavour::Next,
+            Flavour::Custom,
+        ] {
+            let unnumbered: Version = Version::with_flavour(flavour, 0, 0, 0, 0);
+
+            assert!(!numbered.is_comparable(unnumbered));
+            assert!(!unnumbered.is_comparable(numbered));
         }
+
+        assert!(Version::new(8, 0).is_comparable(numbered));
+        assert!(numbered.is_comparable("GE-Proton9-20".parse().unwrap()));
     }
 }
@@ -0,0 +1,92 @@
+use crate::error::{Error, Kind};
+use crate::prefix::{Prefix, PrefixMeta};
+use crate::{pass, throw, Proton};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus};
+
+/// Runs winetricks against the Wine of a Proton build and the `pfx` of its prefix
+#[derive(Debug)]
+pub struct Winetricks {
+    env: BTreeMap<String, String>,
+    prefix: Prefix,
+}
+
+impl Winetricks {
+    /// Points winetricks at the Wine in `proton` and the prefix it runs in, which must exist
+    ///
+    /// # Errors
+    ///
+    /// Will fail if the prefix does not exist
+    pub fn from_proton(proton: &Proton) -> Result<Winetricks, Error> {
+        let data: &Path = proton.compat.parent().unwrap_or(&proton.compat);
+        let prefix: Prefix = Prefix::open(data, &proton.prefix_name())?;
+
+        // Proton 5.0 and newer keep Wine in `files`, older builds in `dist`
+        let files: PathBuf = proton.tool_dir().join("files");
+        let bin: PathBuf = if files.is_dir() {
+            files.join("bin")
+        } else {
+            proton.tool_dir().join("dist/bin")
+        };
+
+        let wine: String = bin.join("wine").to_string_lossy().to_string();
+
+        let mut env: BTreeMap<String, String> = proton.gen_options().into_iter().collect();
+        env.insert(
+            "WINEPREFIX".to_string(),
+            prefix.path().join("pfx").to_string_lossy().to_string(),
+        );
+        env.insert("WINE".to_string(), wine.clone());
+        env.insert("WINELOADER".to_string(), wine);
+        env.insert(
+            "WINESERVER".to_string(),
+            bin.join("wineserver").to_string_lossy().to_string(),
+        );
+
+        pass!(Winetricks { env, prefix })
+    }
+
+    /// Applies each of `verbs` the prefix does not have yet, or every one of them if `force`,
+    /// recording them in the prefix's metadata. Returns the verbs applied
+    ///
+    /// # Errors
+    ///
+    /// Will fail if winetricks can not be run, a verb fails, or the metadata can not be saved
+    pub fn apply(&self, verbs: &[String], force: bool) -> Result<Vec<String>, Error> {
+        let mut meta: PrefixMeta = PrefixMeta::open(&self.prefix)?;
+        let mut applied: Vec<String> = Vec::new();
+
+        for verb in verbs {
+            if !force && meta.winetricks().contains(verb) {
+                continue;
+            }
+
+            println!(
+                "Applying winetricks {} to prefix {}",
+                verb,
+                self.prefix.name()
+            );
+
+            let status: ExitStatus = match Command::new("winetricks")
+                .args(["-q", verb])
+                .envs(&self.env)
+                .status()
+            {
+                Ok(status) => status,
+                Err(e) => throw!(Kind::Winetricks, "{}", e),
+            };
+
+            if !status.success() {
+                throw!(Kind::Winetricks, "verb '{}' failed: {}", verb, status);
+            }
+
+            // saved after every verb, so those applied are kept if a later one fails
+            meta.record_winetricks(verb);
+            meta.save(&self.prefix)?;
+            applied.insert(applied.len(), verb.clone());
+        }
+
+        pass!(applied)
+    }
+}
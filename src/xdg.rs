@@ -0,0 +1,57 @@
+use crate::error::{Error, Kind};
+use crate::{pass, throw};
+use std::env::var;
+use std::path::PathBuf;
+
+/// Resolves an XDG base directory from `name`, or `fallback` under `$HOME` when unset.
+/// Relative paths are ignored, as the XDG base directory specification requires
+fn base_dir(name: &str, fallback: &str) -> Result<PathBuf, Error> {
+    if let Ok(val) = var(name) {
+        let dir: PathBuf = PathBuf::from(val);
+
+        if dir.is_absolute() {
+            return pass!(dir);
+        }
+    }
+
+    match var("HOME") {
+        Ok(home) => pass!(PathBuf::from(home).join(fallback)),
+        Err(_) => throw!(Kind::Environment, "{} / HOME missing", name),
+    }
+}
+
+/// Returns `$XDG_CONFIG_HOME`, or `$HOME/.config`
+///
+/// # Errors
+///
+/// Will fail if neither `XDG_CONFIG_HOME` nor `HOME` are set
+pub fn config_home() -> Result<PathBuf, Error> {
+    base_dir("XDG_CONFIG_HOME", ".config")
+}
+
+/// Returns `$XDG_CACHE_HOME`, or `$HOME/.cache`
+///
+/// # Errors
+///
+/// Will fail if neither `XDG_CACHE_HOME` nor `HOME` are set
+pub fn cache_home() -> Result<PathBuf, Error> {
+    base_dir("XDG_CACHE_HOME", ".cache")
+}
+
+/// Returns `$XDG_DATA_HOME`, or `$HOME/.local/share`
+///
+/// # Errors
+///
+/// Will fail if neither `XDG_DATA_HOME` nor `HOME` are set
+pub fn data_home() -> Result<PathBuf, Error> {
+    base_dir("XDG_DATA_HOME", ".local/share")
+}
+
+/// Returns `$XDG_STATE_HOME`, or `$HOME/.local/state`
+///
+/// # Errors
+///
+/// Will fail if neither `XDG_STATE_HOME` nor `HOME` are set
+pub fn state_home() -> Result<PathBuf, Error> {
+    base_dir("XDG_STATE_HOME", ".local/state")
+}